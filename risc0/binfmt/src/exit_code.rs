@@ -87,6 +87,30 @@ impl ExitCode {
     pub fn is_ok(&self) -> bool {
         matches!(self, ExitCode::Halted(0) | ExitCode::Paused(0))
     }
+
+    /// True if the exit code is Halted(0), indicating the guest ran to completion with an ok
+    /// status.
+    pub fn is_success(&self) -> bool {
+        matches!(self, ExitCode::Halted(0))
+    }
+
+    /// True if the exit code is Halted, with any user exit code.
+    pub fn is_halt(&self) -> bool {
+        matches!(self, ExitCode::Halted(_))
+    }
+
+    /// True if the exit code is Paused, with any user exit code.
+    pub fn is_pause(&self) -> bool {
+        matches!(self, ExitCode::Paused(_))
+    }
+
+    /// The user exit code set by the guest, for Halted and Paused exit codes.
+    pub fn user_code(&self) -> Option<u32> {
+        match self {
+            ExitCode::Halted(user_exit) | ExitCode::Paused(user_exit) => Some(*user_exit),
+            ExitCode::SystemSplit | ExitCode::SessionLimit => None,
+        }
+    }
 }
 
 impl Eq for ExitCode {}
@@ -104,3 +128,25 @@ impl fmt::Display for InvalidExitCodeError {
 
 #[cfg(feature = "std")]
 impl std::error::Error for InvalidExitCodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::ExitCode;
+
+    #[test]
+    fn predicates() {
+        assert!(ExitCode::Halted(0).is_success());
+        assert!(!ExitCode::Halted(1).is_success());
+        assert!(!ExitCode::Paused(0).is_success());
+
+        assert!(ExitCode::Halted(1).is_halt());
+        assert!(!ExitCode::Paused(1).is_halt());
+        assert!(ExitCode::Paused(1).is_pause());
+        assert!(!ExitCode::SystemSplit.is_pause());
+
+        assert_eq!(ExitCode::Halted(3).user_code(), Some(3));
+        assert_eq!(ExitCode::Paused(4).user_code(), Some(4));
+        assert_eq!(ExitCode::SystemSplit.user_code(), None);
+        assert_eq!(ExitCode::SessionLimit.user_code(), None);
+    }
+}
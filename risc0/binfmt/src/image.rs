@@ -15,8 +15,9 @@
 extern crate alloc;
 
 use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::fmt;
 
-use anyhow::{ensure, Result};
+use anyhow::{anyhow, ensure, Result};
 use risc0_zkp::core::{
     digest::Digest,
     hash::sha::{cpu::Impl, Sha256, BLOCK_BYTES, SHA256_INIT},
@@ -24,6 +25,7 @@ use risc0_zkp::core::{
 use risc0_zkvm_platform::{
     memory::{GUEST_MAX_MEM, MEM_SIZE, PAGE_TABLE},
     syscall::DIGEST_BYTES,
+    WORD_SIZE,
 };
 use serde::{Deserialize, Serialize};
 
@@ -46,6 +48,60 @@ pub struct MemoryImage {
     pub pc: u32,
 }
 
+/// Error returned when a page size is not a power of two or not a multiple of
+/// [WORD_SIZE].
+#[derive(Debug, Copy, Clone)]
+pub struct InvalidPageSizeError(pub u32);
+
+impl fmt::Display for InvalidPageSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid page size {}: must be a power of two and a multiple of {WORD_SIZE}",
+            self.0
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidPageSizeError {}
+
+/// Error returned by [MemoryImage::verify_root] when the page table of an image
+/// does not match its pages.
+#[derive(Debug, Copy, Clone)]
+pub enum ImageError {
+    /// The page table entry for a page differs from the hash of that page.
+    PageTableMismatch {
+        /// Index of the page.
+        page_idx: u32,
+        /// Hash of the page.
+        expected: Digest,
+        /// Hash stored in the page table.
+        actual: Digest,
+    },
+    /// The page holding the root of the page table is missing.
+    MissingRootPage,
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::PageTableMismatch {
+                page_idx,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "invalid page table entry for page {page_idx}: {expected} != {actual}"
+            ),
+            Self::MissingRootPage => write!(f, "missing root page"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ImageError {}
+
 #[derive(Clone, Serialize, Deserialize)]
 struct PersistentPageTableInfo {
     page_size: u32,
@@ -187,9 +243,72 @@ impl MemoryImage {
         }
 
         img.hash_pages();
+
+        Ok(img)
+    }
+
+    /// Construct the initial memory image for `program` like [MemoryImage::new],
+    /// then check it with [MemoryImage::verify_root].
+    ///
+    /// The page table is consistent by construction, so this only costs an
+    /// extra pass over the pages; use it to double check images built from
+    /// untrusted inputs.
+    pub fn new_verified(program: &Program, page_size: u32) -> Result<Self> {
+        let img = Self::new(program, page_size)?;
+        img.verify_root().map_err(|err| anyhow!(err))?;
         Ok(img)
     }
 
+    /// Construct the initial memory image for a flat binary
+    ///
+    /// The bytes of `flat` are loaded contiguously starting at `load_addr`,
+    /// padded with zeros to a whole number of words, and execution begins at
+    /// `entry`. Both addresses must be word-aligned, and `entry` must point
+    /// into the loaded binary.
+    pub fn from_flat(flat: &[u8], load_addr: u32, entry: u32, page_size: u32) -> Result<Self> {
+        ensure!(
+            load_addr % WORD_SIZE as u32 == 0,
+            "load address 0x{load_addr:08x} is not word-aligned"
+        );
+        ensure!(
+            entry % WORD_SIZE as u32 == 0,
+            "entry point 0x{entry:08x} is not word-aligned"
+        );
+        let end = u32::try_from(flat.len())
+            .ok()
+            .and_then(|len| load_addr.checked_add(len))
+            .ok_or_else(|| anyhow!("flat binary does not fit in the address space"))?;
+        ensure!(
+            (load_addr..end).contains(&entry),
+            "entry point 0x{entry:08x} is outside the loaded binary"
+        );
+
+        let image = flat
+            .chunks(WORD_SIZE)
+            .zip((load_addr..).step_by(WORD_SIZE))
+            .map(|(chunk, addr)| {
+                let mut word = [0u8; WORD_SIZE];
+                word[..chunk.len()].copy_from_slice(chunk);
+                (addr, u32::from_le_bytes(word))
+            })
+            .collect();
+        Self::new(&Program { entry, image }, page_size)
+    }
+
+    /// Construct the initial memory image for the ELF binary `elf`, split into
+    /// pages of `page_size` bytes
+    ///
+    /// This is useful to study paging overhead with page sizes other than
+    /// the default. `page_size` must be a power of two and a multiple of
+    /// [WORD_SIZE]; otherwise an [InvalidPageSizeError] is returned.
+    pub fn from_elf_with_page_size(elf: &[u8], page_size: u32) -> Result<Self> {
+        if !page_size.is_power_of_two() || page_size % WORD_SIZE as u32 != 0 {
+            return Err(anyhow!(InvalidPageSizeError(page_size)));
+        }
+        let program = Program::load_elf(elf, GUEST_MAX_MEM as u32)?;
+        Self::new(&program, page_size)
+    }
+
     /// Load a page specified by page_idx. If no page is found, a zero page is
     /// returned.
     pub fn load_page(&self, page_idx: u32) -> Vec<u8> {
@@ -266,6 +385,38 @@ impl MemoryImage {
         }
     }
 
+    /// Verify that the page table of this image matches its pages.
+    ///
+    /// Recomputes the hash of every page and compares it to the corresponding
+    /// page table entry, so that the root returned by
+    /// [MemoryImage::compute_root_hash] commits to the actual contents of the
+    /// image. This is useful to detect corruption of an image loaded from an
+    /// untrusted source before executing it.
+    pub fn verify_root(&self) -> core::result::Result<(), ImageError> {
+        for page_idx in 0..self.info.root_idx {
+            let expected = self.hash_page(page_idx);
+            let entry_addr = self.info.get_page_entry_addr(page_idx);
+            let entry_page_idx = self.info.get_page_index(entry_addr);
+            let mut entry = [0_u8; DIGEST_BYTES];
+            if let Some(page) = self.pages.get(&entry_page_idx) {
+                let offset = (entry_addr - self.info.get_page_addr(entry_page_idx)) as usize;
+                entry.copy_from_slice(&page[offset..offset + DIGEST_BYTES]);
+            }
+            let actual = Digest::from(entry);
+            if expected != actual {
+                return Err(ImageError::PageTableMismatch {
+                    page_idx,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        if !self.pages.contains_key(&self.info.root_idx) {
+            return Err(ImageError::MissingRootPage);
+        }
+        Ok(())
+    }
+
     /// Verify the integrity of the MemoryImage.
     ///
     /// Confirms that the page table is a valid Merkle tree with the expected
@@ -346,7 +497,7 @@ mod tests {
     };
     use test_log::test;
 
-    use crate::{elf::Program, image::PageTableInfo, MemoryImage};
+    use crate::{elf::Program, image::PageTableInfo, ImageError, InvalidPageSizeError, MemoryImage};
 
     fn page_table_size(max_mem: u32, page_size: u32) -> u32 {
         PageTableInfo::new(max_mem, page_size)
@@ -371,6 +522,43 @@ mod tests {
         image.check(image.info.root_page_addr).unwrap();
     }
 
+    #[test]
+    fn verify_root() {
+        const PAGE_SIZE: u32 = 1024;
+        let program = Program::load_elf(MULTI_TEST_ELF, GUEST_MAX_MEM as u32).unwrap();
+        let mut image = MemoryImage::new_verified(&program, PAGE_SIZE).unwrap();
+        image.verify_root().unwrap();
+
+        // Corrupt a page without updating the page table.
+        image.store_region_in_page(TEXT_START, &[0xff; 4]);
+        assert!(matches!(
+            image.verify_root(),
+            Err(ImageError::PageTableMismatch { .. })
+        ));
+
+        // Rehashing the pages restores consistency.
+        image.hash_pages();
+        image.verify_root().unwrap();
+    }
+
+    #[test]
+    fn from_elf_with_page_size() {
+        const PAGE_SIZE: u32 = 2048;
+        let program = Program::load_elf(MULTI_TEST_ELF, GUEST_MAX_MEM as u32).unwrap();
+        let expected = MemoryImage::new(&program, PAGE_SIZE).unwrap();
+        let image = MemoryImage::from_elf_with_page_size(MULTI_TEST_ELF, PAGE_SIZE).unwrap();
+        assert_eq!(image.info.page_size, PAGE_SIZE);
+        assert_eq!(image.compute_id(), expected.compute_id());
+
+        for page_size in [0, 2, 1000] {
+            let err = MemoryImage::from_elf_with_page_size(MULTI_TEST_ELF, page_size)
+                .err()
+                .unwrap();
+            let err = err.downcast_ref::<InvalidPageSizeError>().unwrap();
+            assert_eq!(err.0, page_size);
+        }
+    }
+
     #[test]
     fn page_table_info() {
         const PAGE_SIZE_1K: u32 = 1024;
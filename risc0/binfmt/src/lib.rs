@@ -24,7 +24,7 @@ mod image;
 mod sys_state;
 
 #[cfg(not(target_os = "zkvm"))]
-pub use self::image::{MemoryImage, PageTableInfo};
+pub use self::image::{ImageError, InvalidPageSizeError, MemoryImage, PageTableInfo};
 pub use crate::{
     elf::Program,
     exit_code::{ExitCode, InvalidExitCodeError},
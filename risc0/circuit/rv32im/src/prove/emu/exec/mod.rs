@@ -15,9 +15,9 @@
 #[cfg(test)]
 mod tests;
 
-use std::{array, cell::RefCell, collections::BTreeSet, mem, rc::Rc};
+use std::{array, cell::RefCell, collections::BTreeSet, fmt, mem, rc::Rc};
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use crypto_bigint::{CheckedMul as _, Encoding as _, NonZero, U256, U512};
 use risc0_binfmt::{ExitCode, MemoryImage, Program, SystemState};
 use risc0_zkp::{
@@ -57,6 +57,64 @@ use crate::{
 
 pub const DEFAULT_SEGMENT_LIMIT_PO2: usize = 20;
 
+// at least one HaltCycle needs to appear in the body
+const MIN_HALT_CYCLES: usize = 1;
+// a final "is_done" PageFault cycle is required when a split occurs
+const PAGE_FINI_CYCLES: usize = 1;
+
+/// The number of cycles in every segment that are reserved for setup, teardown, and ZK padding,
+/// and so are not available to the guest.
+pub const RESERVED_CYCLES: usize =
+    INIT_CYCLES + MIN_HALT_CYCLES + PAGE_FINI_CYCLES + FINI_CYCLES + ZK_CYCLES;
+
+/// The number of cycles in a segment of `2^segment_po2` cycles that are available to the guest,
+/// after the [RESERVED_CYCLES].
+///
+/// Paging in and out memory that the guest touches is also charged against this budget, so a
+/// segment can split before the guest has executed this many instruction cycles. Returns `None`
+/// if `2^segment_po2` does not fit in a `u64` or is smaller than the reserved cycles.
+pub fn usable_cycles_per_segment(segment_po2: u32) -> Option<u64> {
+    1u64.checked_shl(segment_po2)?
+        .checked_sub(RESERVED_CYCLES as u64)
+}
+
+/// An error raised while executing a guest that callers may want to match on.
+///
+/// Returned wrapped in an [anyhow::Error]; use [anyhow::Error::downcast_ref] to recover it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExecutorError {
+    /// The guest loaded from an address that is not aligned to the size of the load.
+    LoadAddressMisaligned {
+        /// The address of the load.
+        addr: u32,
+        /// The program counter of the load instruction.
+        pc: u32,
+    },
+    /// The guest stored to an address that is not aligned to the size of the store.
+    StoreAddressMisaligned {
+        /// The address of the store.
+        addr: u32,
+        /// The program counter of the store instruction.
+        pc: u32,
+    },
+}
+
+impl fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecutorError::LoadAddressMisaligned { addr, pc } => {
+                write!(f, "Trap: LoadAddressMisaligned(0x{addr:08x}), pc: 0x{pc:08x}")
+            }
+            ExecutorError::StoreAddressMisaligned { addr, pc } => {
+                write!(f, "Trap: StoreAddressMisaligned(0x{addr:08x}), pc: 0x{pc:08x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExecutorError {}
+
 /// A host-side implementation of a system call.
 pub trait Syscall {
     /// Invokes the system call.
@@ -101,6 +159,7 @@ pub struct ExecutorResult {
     pub pre_state: SystemState,
     pub post_state: SystemState,
     pub output_digest: Option<Digest>,
+    pub breakpoint: bool,
 }
 
 #[derive(Default)]
@@ -137,6 +196,9 @@ pub struct Executor<'a, 'b, S: Syscall> {
     pending: PendingState,
     trace: Vec<Rc<RefCell<dyn TraceCallback + 'b>>>,
     cycles: SessionCycles,
+    break_on_write: Option<WordAddr>,
+    breakpoint: bool,
+    fixed_segment_cycles: Option<usize>,
 }
 
 impl PendingState {
@@ -177,22 +239,39 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
             },
             trace,
             cycles: SessionCycles::default(),
+            break_on_write: None,
+            breakpoint: false,
+            fixed_segment_cycles: None,
         }
     }
 
+    /// Split segments after a fixed number of instruction cycles.
+    ///
+    /// Each split happens before the first instruction that would take the
+    /// segment past `cycles`, independent of how many cycles are spent on
+    /// paging, so segment boundaries only depend on the executed instructions.
+    /// Execution fails if a segment with this many cycles does not fit in the
+    /// segment limit.
+    pub fn set_fixed_segment_cycles(&mut self, cycles: usize) {
+        self.fixed_segment_cycles = Some(cycles);
+    }
+
+    /// Stop execution after the first instruction that stores to the word
+    /// containing `addr`.
+    ///
+    /// The final segment ends with [ExitCode::SessionLimit] and the
+    /// [ExecutorResult] is flagged as a breakpoint stop.
+    pub fn set_break_on_write(&mut self, addr: ByteAddr) {
+        self.break_on_write = Some(addr.waddr());
+    }
+
     pub fn run<F: FnMut(Segment) -> Result<()>>(
         &mut self,
         segment_po2: usize,
         max_cycles: Option<u64>,
         mut callback: F,
     ) -> Result<ExecutorResult> {
-        // at least one HaltCycle needs to appear in the body
-        const MIN_HALT_CYCLES: usize = 1;
-        // a final "is_done" PageFault cycle is required when a split occurs
-        const PAGE_FINI_CYCLES: usize = 1;
         // leave room for reserved cycles
-        const RESERVED_CYCLES: usize =
-            INIT_CYCLES + MIN_HALT_CYCLES + PAGE_FINI_CYCLES + FINI_CYCLES + ZK_CYCLES;
         let segment_limit = (1 << segment_po2) - RESERVED_CYCLES;
 
         self.reset();
@@ -215,13 +294,21 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
             emu.step(self)?;
 
             let segment_cycles = self.insn_cycles + self.pager.cycles + self.pending.cycles;
-            if segment_cycles < segment_limit {
+            let fits_fixed = self
+                .fixed_segment_cycles
+                .map_or(true, |fixed| self.insn_cycles + self.pending.cycles <= fixed);
+            if segment_cycles < segment_limit && fits_fixed {
                 self.advance()?;
             } else if self.insn_cycles == 0 {
                 bail!(
                     "segment limit ({segment_limit}) too small for instruction at pc: {:?}",
                     self.pc
                 );
+            } else if let (true, Some(fixed)) = (fits_fixed, self.fixed_segment_cycles) {
+                bail!(
+                    "fixed segment cycles ({fixed}) do not fit in segment limit ({segment_limit}) at pc: {:?}",
+                    self.pc
+                );
             } else {
                 self.pager.undo();
                 let used_cycles = self.insn_cycles + self.pager.cycles + RESERVED_CYCLES;
@@ -296,6 +383,7 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
             pre_state: initial_state,
             post_state,
             output_digest: self.output_digest,
+            breakpoint: self.breakpoint,
         })
     }
 
@@ -334,6 +422,7 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
         self.exit_code = None;
         self.syscalls.clear();
         self.output_digest = None;
+        self.breakpoint = false;
         self.pending.reset(self.pc);
         self.cycles.user = 0;
         self.cycles.total = 0;
@@ -640,9 +729,18 @@ impl<'a, 'b, S: Syscall> EmuContext for Executor<'a, 'b, S> {
     }
 
     fn trap(&self, cause: TrapCause) -> Result<bool> {
-        let msg = format!("Trap: {cause:08x?}, pc: {:?}", self.pc);
-        tracing::info!("{msg}");
-        bail!("{msg}");
+        let pc = self.pc.0;
+        let err = match cause {
+            TrapCause::LoadAddressMisaligned(addr) => {
+                ExecutorError::LoadAddressMisaligned { addr: addr.0, pc }.into()
+            }
+            TrapCause::StoreAddressMisaligned(addr) => {
+                ExecutorError::StoreAddressMisaligned { addr: addr.0, pc }.into()
+            }
+            cause => anyhow!("Trap: {cause:08x?}, pc: {:?}", self.pc),
+        };
+        tracing::info!("{err}");
+        Err(err)
     }
 
     fn check_data_load(&self, addr: ByteAddr) -> bool {
@@ -706,6 +804,11 @@ impl<'a, 'b, S: Syscall> EmuContext for Executor<'a, 'b, S> {
                 region: data.to_le_bytes().to_vec(),
             });
         }
+        if self.break_on_write == Some(addr) {
+            tracing::debug!("breakpoint: store to {:?}", addr.baddr());
+            self.breakpoint = true;
+            self.pending.exit_code = Some(ExitCode::SessionLimit);
+        }
         self.raw_store_memory(addr, data)
     }
 }
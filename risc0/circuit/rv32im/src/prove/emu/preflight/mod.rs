@@ -15,7 +15,7 @@
 #[cfg(test)]
 mod tests;
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 use anyhow::{anyhow, bail, ensure, Result};
 use crypto_bigint::{CheckedMul as _, Encoding as _, NonZero, U256, U512};
@@ -110,6 +110,7 @@ struct Preflight {
     halted: Option<u32>,
     syscalls: VecDeque<SyscallRecord>,
     input_digest: Digest,
+    insn_counts: HashMap<InsnKind, u64>,
 }
 
 impl PreflightCycle {
@@ -167,6 +168,7 @@ impl Preflight {
             halted: None,
             syscalls: segment.syscalls.clone().into(),
             input_digest: segment.input_digest,
+            insn_counts: HashMap::new(),
         }
     }
 
@@ -720,6 +722,7 @@ impl EmuContext for Preflight {
     }
 
     fn on_normal_end(&mut self, insn: &Instruction, _decoded: &DecodedInstruction) {
+        *self.insn_counts.entry(insn.kind).or_default() += 1;
         match insn.kind {
             InsnKind::AND
             | InsnKind::ANDI
@@ -788,6 +791,16 @@ impl Segment {
     #[tracing::instrument(skip_all)]
     pub fn preflight(&self) -> Result<PreflightTrace> {
         tracing::debug!("preflight: {self:#?}");
+        Ok(self.run_preflight()?.trace)
+    }
+
+    /// Count how many times each kind of instruction was executed in this
+    /// segment by replaying it.
+    pub fn instruction_histogram(&self) -> Result<HashMap<InsnKind, u64>> {
+        Ok(self.run_preflight()?.insn_counts)
+    }
+
+    fn run_preflight(&self) -> Result<Preflight> {
         let mut preflight = Preflight::new(self);
         let mut emu = Emulator::new();
 
@@ -798,6 +811,6 @@ impl Segment {
         }
         preflight.post_steps()?;
 
-        Ok(preflight.trace)
+        Ok(preflight)
     }
 }
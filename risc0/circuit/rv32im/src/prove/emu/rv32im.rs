@@ -78,7 +78,7 @@ pub enum TrapCause {
     InstructionAccessFault,
     IllegalInstruction(u32),
     Breakpoint,
-    LoadAddressMisaligned,
+    LoadAddressMisaligned(ByteAddr),
     LoadAccessFault,
     StoreAddressMisaligned(ByteAddr),
     StoreAccessFault,
@@ -106,7 +106,7 @@ enum InsnCategory {
     Invalid,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum InsnKind {
     INVALID,
     ADD,
@@ -535,7 +535,7 @@ impl Emulator {
             }
             InsnKind::LH => {
                 if addr.0 & 0x01 != 0 {
-                    return ctx.trap(TrapCause::LoadAddressMisaligned);
+                    return ctx.trap(TrapCause::LoadAddressMisaligned(addr));
                 }
                 let mut out = (data >> shift) & 0xffff;
                 if out & 0x8000 != 0 {
@@ -545,14 +545,14 @@ impl Emulator {
             }
             InsnKind::LW => {
                 if addr.0 & 0x03 != 0 {
-                    return ctx.trap(TrapCause::LoadAddressMisaligned);
+                    return ctx.trap(TrapCause::LoadAddressMisaligned(addr));
                 }
                 data
             }
             InsnKind::LBU => (data >> shift) & 0xff,
             InsnKind::LHU => {
                 if addr.0 & 0x01 != 0 {
-                    return ctx.trap(TrapCause::LoadAddressMisaligned);
+                    return ctx.trap(TrapCause::LoadAddressMisaligned(addr));
                 }
                 (data >> shift) & 0xffff
             }
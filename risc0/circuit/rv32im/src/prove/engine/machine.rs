@@ -156,6 +156,22 @@ impl MachineContext {
         }
     }
 
+    /// Number of bytes held by the preflight trace and the copies of it made for the circuit.
+    ///
+    /// Allocations made by the circuit library itself, such as those for sorting, are not
+    /// included.
+    pub fn allocated_bytes(&self) -> usize {
+        fn stage_bytes(stage: &PreflightStage) -> usize {
+            vec_bytes(&stage.cycles) + vec_bytes(&stage.txns) + vec_bytes(&stage.extras)
+        }
+        stage_bytes(&self.trace.pre)
+            + stage_bytes(&self.trace.body)
+            + core::mem::size_of::<RawPreflightTrace>()
+            + vec_bytes(&self._raw_cycles)
+            + vec_bytes(&self._raw_txns)
+            + vec_bytes(&self._raw_extras)
+    }
+
     pub fn is_exec_par_safe(&self, cycle: usize) -> bool {
         let cur_cycle = self.get_cycle(cycle);
         // let is_safe = cur_cycle.back.is_some();
@@ -369,3 +385,7 @@ impl MachineContext {
         &stage.cycles[cycle - offset]
     }
 }
+
+fn vec_bytes<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * core::mem::size_of::<T>()
+}
@@ -35,7 +35,7 @@ use risc0_zkp::{
 };
 
 use self::witgen::WitnessGenerator;
-use super::{segment::Segment, Seal, SegmentProver};
+use super::{segment::Segment, ProveStats, Seal, SegmentProver};
 use crate::{CircuitImpl, CIRCUIT, REGISTER_GROUP_ACCUM, REGISTER_GROUP_CTRL, REGISTER_GROUP_DATA};
 
 struct Twin(Elem, Elem);
@@ -66,8 +66,12 @@ where
     H: Hal<Field = BabyBear, Elem = BabyBearElem, ExtElem = BabyBearExtElem>,
     C: CircuitHal<H>,
 {
-    #[tracing::instrument(skip_all)]
     fn prove_segment(&self, segment: &Segment) -> Result<Seal> {
+        Ok(self.prove_segment_with_stats(segment)?.0)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn prove_segment_with_stats(&self, segment: &Segment) -> Result<(Seal, ProveStats)> {
         nvtx::range_push!("prove_segment");
 
         nvtx::range_push!("preflight");
@@ -83,6 +87,9 @@ where
         nvtx::range_pop!();
         witgen.execute(trace)?;
         let steps = witgen.steps;
+        let stats = ProveStats {
+            witness_peak_bytes: witgen.peak_allocated_bytes(),
+        };
 
         let seal = tracing::info_span!("prove").in_scope(|| {
             nvtx::range_push!("prove");
@@ -168,7 +175,7 @@ where
         });
 
         nvtx::range_pop!();
-        Ok(seal)
+        Ok((seal, stats))
     }
 }
 
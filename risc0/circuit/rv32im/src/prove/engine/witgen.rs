@@ -33,24 +33,52 @@ pub struct WitnessGenerator {
     pub ctrl: CpuBuffer<BabyBearElem>,
     pub data: CpuBuffer<BabyBearElem>,
     pub io: CpuBuffer<BabyBearElem>,
+    allocs: AllocTracker,
+}
+
+/// Counts the bytes held by the allocations made during witness generation,
+/// recording the high-water mark.
+#[derive(Default)]
+struct AllocTracker {
+    current: usize,
+    peak: usize,
+}
+
+impl AllocTracker {
+    fn alloc(&mut self, bytes: usize) {
+        self.current += bytes;
+        self.peak = self.peak.max(self.current);
+    }
+
+    fn free(&mut self, bytes: usize) {
+        self.current -= bytes;
+    }
+}
+
+fn buffer_bytes(buffer: &CpuBuffer<BabyBearElem>) -> usize {
+    buffer.as_slice().len() * core::mem::size_of::<BabyBearElem>()
 }
 
 impl WitnessGenerator {
     pub fn new(po2: usize, io: &[BabyBearElem]) -> Self {
         let steps = 1 << po2;
+        let mut allocs = AllocTracker::default();
 
         nvtx::range_push!("alloc(ctrl)");
         let ctrl = CpuBuffer::from_fn("ctrl", steps * CIRCUIT.ctrl_size(), |_| BabyBearElem::ZERO);
+        allocs.alloc(buffer_bytes(&ctrl));
         nvtx::range_pop!();
 
         nvtx::range_push!("alloc(data)");
         let data = CpuBuffer::from_fn("data", steps * CIRCUIT.data_size(), |_| {
             BabyBearElem::INVALID
         });
+        allocs.alloc(buffer_bytes(&data));
         nvtx::range_pop!();
 
         nvtx::range_push!("alloc(io)");
         let io = CpuBuffer::from(Vec::from(io));
+        allocs.alloc(buffer_bytes(&io));
         nvtx::range_pop!();
 
         Self {
@@ -58,17 +86,29 @@ impl WitnessGenerator {
             ctrl,
             data,
             io,
+            allocs,
         }
     }
 
+    /// Highest number of bytes held at once by the allocations made so far for
+    /// the witness: the witness buffers and, while [WitnessGenerator::execute]
+    /// runs, the preflight trace and the copies of it made for the circuit.
+    pub fn peak_allocated_bytes(&self) -> usize {
+        self.allocs.peak
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn execute(&mut self, trace: PreflightTrace) -> Result<()> {
         nvtx::range_push!("witgen");
 
         let mut machine = MachineContext::new(self.steps, trace);
+        let machine_bytes = machine.allocated_bytes();
+        self.allocs.alloc(machine_bytes);
         self.compute_execute(&mut machine)?;
         self.compute_verify_ram(&mut machine)?;
         self.compute_verify_bytes(&mut machine)?;
+        drop(machine);
+        self.allocs.free(machine_bytes);
         let mut rng = thread_rng();
 
         {
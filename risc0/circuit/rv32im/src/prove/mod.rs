@@ -24,8 +24,27 @@ use self::segment::Segment;
 
 pub type Seal = Vec<u32>;
 
+/// Resource usage recorded while proving a segment.
+#[derive(Clone, Debug, Default)]
+pub struct ProveStats {
+    /// Peak number of bytes allocated on the host for the witness.
+    ///
+    /// This is the high-water mark of the allocations made during witness
+    /// generation: the witness buffers, the preflight trace and the copies of it
+    /// made for the circuit. Allocations made inside the native circuit library
+    /// are not counted.
+    pub witness_peak_bytes: usize,
+}
+
 pub trait SegmentProver {
     fn prove_segment(&self, segment: &Segment) -> Result<Seal>;
+
+    /// Prove the segment, also returning the resources used to do so.
+    ///
+    /// Provers that do not record any statistics return [ProveStats::default].
+    fn prove_segment_with_stats(&self, segment: &Segment) -> Result<(Seal, ProveStats)> {
+        Ok((self.prove_segment(segment)?, ProveStats::default()))
+    }
 }
 
 pub fn get_segment_prover() -> Box<dyn SegmentProver> {
@@ -17,6 +17,8 @@
 mod fri;
 mod merkle;
 mod read_iop;
+#[cfg(feature = "std")]
+pub mod soundness;
 
 use alloc::{vec, vec::Vec};
 use core::{cell::RefCell, fmt, iter::zip};
@@ -40,12 +42,18 @@ use crate::{
 pub enum VerificationError {
     ReceiptFormatError,
     ControlVerificationError { control_id: Digest },
+    ControlRootMismatch {
+        control_root: Digest,
+        candidates: Vec<Digest>,
+    },
     ImageVerificationError,
     MerkleQueryOutOfRange { idx: usize, rows: usize },
     InvalidProof,
     JournalDigestMismatch,
     UnexpectedExitCode,
     InvalidHashSuite,
+    MixedHashFunctions,
+    InsufficientSoundness { bits: f32, required: f32 },
 }
 
 impl fmt::Debug for VerificationError {
@@ -61,6 +69,19 @@ impl fmt::Display for VerificationError {
             VerificationError::ControlVerificationError { control_id } => {
                 write!(f, "control_id mismatch: {control_id}")
             }
+            VerificationError::ControlRootMismatch {
+                control_root,
+                candidates,
+            } => {
+                write!(f, "control_root mismatch: {control_root} not in [")?;
+                for (i, candidate) in candidates.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{candidate}")?;
+                }
+                write!(f, "]")
+            }
             VerificationError::ImageVerificationError => write!(f, "image_id mismatch"),
             VerificationError::MerkleQueryOutOfRange { idx, rows } => write!(
                 f,
@@ -72,6 +93,13 @@ impl fmt::Display for VerificationError {
             }
             VerificationError::UnexpectedExitCode => write!(f, "Unexpected exit_code"),
             VerificationError::InvalidHashSuite => write!(f, "Invalid hash suite"),
+            VerificationError::MixedHashFunctions => {
+                write!(f, "Receipt segments use different hash functions")
+            }
+            VerificationError::InsufficientSoundness { bits, required } => write!(
+                f,
+                "Proven soundness of {bits} bits is below the required {required} bits"
+            ),
         }
     }
 }
@@ -399,7 +399,7 @@ impl Datasheet {
     fn compact(&mut self) {
         println!("compact");
 
-        let opts = ProverOpts::compact();
+        let opts = ProverOpts::groth16();
         let prover = get_prover_server(&opts).unwrap();
 
         let env = ExecutorEnv::builder()
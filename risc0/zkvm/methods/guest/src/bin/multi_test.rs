@@ -22,17 +22,21 @@ extern crate alloc;
 use alloc::{
     alloc::{alloc_zeroed, Layout},
     format, vec,
+    vec::Vec,
 };
 use core::arch::asm;
 
 use getrandom::getrandom;
 use risc0_zkp::core::hash::sha::testutil::test_sha_impl;
 use risc0_zkvm::{
-    guest::{env, memory_barrier, sha},
+    guest::{
+        env::{self, Read},
+        memory_barrier, sha,
+    },
     sha::{Digest, Sha256},
     ReceiptClaim,
 };
-use risc0_zkvm_methods::multi_test::{MultiTestSpec, SYS_MULTI_TEST};
+use risc0_zkvm_methods::multi_test::{MultiTestSpec, TestConfig, SYS_MULTI_TEST};
 use risc0_zkvm_platform::{
     fileno,
     memory::{self, SYSTEM},
@@ -185,6 +189,13 @@ fn main() {
                 env::verify(image_id, &journal).unwrap();
             }
         }
+        MultiTestSpec::SysVerifyAll(pairs) => {
+            let count = env::assumption_count();
+            for (image_id, journal) in pairs.into_iter().take(count) {
+                env::verify(image_id, &journal).unwrap();
+            }
+            env::commit(&(count as u32));
+        }
         MultiTestSpec::SysVerifyIntegrity { claim_words } => {
             let claim: ReceiptClaim = risc0_zkvm::serde::from_slice(&claim_words).unwrap();
             env::verify_integrity(&claim).unwrap();
@@ -192,6 +203,65 @@ fn main() {
         MultiTestSpec::Echo { bytes } => {
             env::commit_slice(&bytes);
         }
+        MultiTestSpec::FetchBlob(digest) => {
+            let blob = env::fetch_blob(&digest);
+            env::commit(&(blob.len() as u32));
+        }
+        MultiTestSpec::RunId => {
+            env::commit(&env::run_id());
+        }
+        MultiTestSpec::Config => {
+            let config: TestConfig = env::config();
+            env::commit(&config.threshold);
+        }
+        MultiTestSpec::EmitEvents(events) => {
+            for event in events {
+                env::emit_event(&event);
+            }
+        }
+        MultiTestSpec::CommitKv(pairs) => {
+            for (key, value) in pairs {
+                env::commit_kv(&key, &value);
+            }
+        }
+        MultiTestSpec::CommitMap(pairs) => {
+            env::commit_map(&pairs.into_iter().collect());
+        }
+        MultiTestSpec::ReadChannels(channels) => {
+            for channel in channels {
+                let data: Vec<u8> = env::channel(channel).read();
+                env::commit(&data);
+            }
+        }
+        MultiTestSpec::StdinChecksum => {
+            let mut buf = [0u8; 4096];
+            let mut len = 0u64;
+            let mut checksum = 0u32;
+            loop {
+                let nread = unsafe { sys_read(fileno::STDIN, buf.as_mut_ptr(), buf.len()) };
+                if nread == 0 {
+                    break;
+                }
+                for byte in &buf[..nread] {
+                    checksum = checksum.wrapping_mul(31).wrapping_add(*byte as u32);
+                }
+                len += nread as u64;
+            }
+            env::commit(&(len, checksum));
+        }
+        MultiTestSpec::ReadChunks {
+            chunk_size,
+            busy_iters,
+        } => loop {
+            let chunk = env::read_chunk(chunk_size);
+            if chunk.is_empty() {
+                break;
+            }
+            env::commit_slice(chunk);
+            for i in 0..busy_iters {
+                core::hint::black_box(i);
+            }
+        },
         MultiTestSpec::EchoStdout { nbytes, fd } => {
             // Unaligned buffer size to exercise things a little bit.
             let mut buf = vec![0u8; nbytes as usize];
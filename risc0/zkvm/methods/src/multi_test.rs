@@ -15,7 +15,7 @@
 // Definitions for test selection codes used by the "multi_test" test.
 extern crate alloc;
 
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 
 use risc0_zkvm::{declare_syscall, sha::Digest};
 use risc0_zkvm_platform::syscall::bigint;
@@ -57,6 +57,9 @@ pub enum MultiTestSpec {
         pos_and_len: Vec<(u32, u32)>,
     },
     SysVerify(Vec<(Digest, Vec<u8>)>),
+    /// Verify as many of the given (image_id, journal) pairs as the host has registered
+    /// assumptions, and commit that count.
+    SysVerifyAll(Vec<(Digest, Vec<u8>)>),
     SysVerifyIntegrity {
         // Define this field as a serialized vector to avoid circular dependency issues.
         claim_words: Vec<u32>,
@@ -64,6 +67,25 @@ pub enum MultiTestSpec {
     Echo {
         bytes: Vec<u8>,
     },
+    FetchBlob(Digest),
+    RunId,
+    /// Read a [TestConfig] with `env::config` and commit its `threshold`.
+    Config,
+    /// Emit each of the given events with `env::emit_event`.
+    EmitEvents(Vec<Vec<u8>>),
+    /// Commit each of the given pairs with `env::commit_kv`.
+    CommitKv(Vec<(String, Vec<u8>)>),
+    /// Collect the given pairs into a map and commit it with `env::commit_map`.
+    CommitMap(Vec<(String, Vec<u8>)>),
+    /// Read a `Vec<u8>` from each of the given input channels and commit them in order.
+    ReadChannels(Vec<u32>),
+    /// Read the rest of stdin until EOF and commit its length and a checksum of its bytes.
+    StdinChecksum,
+    ReadChunks {
+        chunk_size: u32,
+        /// Number of busy loop iterations to run between chunks
+        busy_iters: u32,
+    },
     EchoStdout {
         nbytes: u32,
         fd: u32,
@@ -92,4 +114,11 @@ pub enum MultiTestSpec {
     AllocZeroed,
 }
 
+/// Configuration passed to the guest by the `Config` test.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TestConfig {
+    pub name: String,
+    pub threshold: u32,
+}
+
 declare_syscall!(pub SYS_MULTI_TEST);
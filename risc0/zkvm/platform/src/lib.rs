@@ -34,11 +34,16 @@ pub const WORD_SIZE: usize = core::mem::size_of::<u32>();
 pub const PAGE_SIZE: usize = 1024;
 
 /// Standard IO file descriptors for use with sys_read and sys_write.
+///
+/// File descriptors below [fileno::FIRST_CHANNEL] are reserved for the system.
+/// Descriptors from [fileno::FIRST_CHANNEL] onwards are free for applications
+/// to use as additional input channels.
 pub mod fileno {
     pub const STDIN: u32 = 0;
     pub const STDOUT: u32 = 1;
     pub const STDERR: u32 = 2;
     pub const JOURNAL: u32 = 3;
+    pub const FIRST_CHANNEL: u32 = 10;
 }
 
 /// Align address upwards.
@@ -128,12 +128,18 @@ macro_rules! declare_syscall {
 pub mod nr {
     declare_syscall!(pub SYS_ARGC);
     declare_syscall!(pub SYS_ARGV);
+    declare_syscall!(pub SYS_ASSUMPTION_COUNT);
+    declare_syscall!(pub SYS_CONFIG);
     declare_syscall!(pub SYS_CYCLE_COUNT);
+    declare_syscall!(pub SYS_EMIT_EVENT);
+    declare_syscall!(pub SYS_FETCH_BLOB);
     declare_syscall!(pub SYS_GETENV);
     declare_syscall!(pub SYS_LOG);
     declare_syscall!(pub SYS_PANIC);
     declare_syscall!(pub SYS_RANDOM);
     declare_syscall!(pub SYS_READ);
+    declare_syscall!(pub SYS_READ_CHUNK);
+    declare_syscall!(pub SYS_RUN_ID);
     declare_syscall!(pub SYS_VERIFY);
     declare_syscall!(pub SYS_VERIFY_INTEGRITY);
     declare_syscall!(pub SYS_WRITE);
@@ -428,6 +434,19 @@ pub unsafe extern "C" fn sys_log(msg_ptr: *const u8, len: usize) {
     syscall_2(nr::SYS_LOG, null_mut(), 0, msg_ptr as u32, len as u32);
 }
 
+/// Emit a diagnostic event to the host.
+///
+/// Events are collected by the host alongside the session but, unlike the
+/// journal, are not part of the committed output.
+///
+/// # Safety
+///
+/// `event_ptr` must be aligned and dereferenceable.
+#[cfg_attr(feature = "export-syscalls", no_mangle)]
+pub unsafe extern "C" fn sys_emit_event(event_ptr: *const u8, len: usize) {
+    syscall_2(nr::SYS_EMIT_EVENT, null_mut(), 0, event_ptr as u32, len as u32);
+}
+
 #[cfg_attr(feature = "export-syscalls", no_mangle)]
 pub extern "C" fn sys_cycle_count() -> u64 {
     let Return(hi, lo) = unsafe { syscall_0(nr::SYS_CYCLE_COUNT, null_mut(), 0) };
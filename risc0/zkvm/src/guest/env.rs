@@ -68,14 +68,18 @@
 //! [proof composition]:https://www.risczero.com/blog/proof-composition
 //! [guest-optimization]: https://dev.risczero.com/api/zkvm/optimization#when-reading-data-as-raw-bytes-use-envread_slice
 
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 use core::{cell::OnceCell, fmt, mem::MaybeUninit};
 
 use bytemuck::Pod;
 use risc0_zkvm_platform::{
     align_up, fileno,
     syscall::{
-        self, sys_alloc_words, sys_cycle_count, sys_halt, sys_input, sys_log, sys_pause, sys_read,
-        sys_read_words, sys_verify, sys_verify_integrity, sys_write, syscall_2, SyscallName,
+        self,
+        nr::{SYS_ASSUMPTION_COUNT, SYS_CONFIG, SYS_FETCH_BLOB, SYS_READ_CHUNK, SYS_RUN_ID},
+        sys_alloc_words, sys_cycle_count, sys_emit_event, sys_halt, sys_input, sys_log,
+        sys_pause, sys_read, sys_read_words, sys_verify, sys_verify_integrity, sys_write,
+        syscall_2, SyscallName,
     },
     WORD_SIZE,
 };
@@ -103,6 +107,9 @@ static mut ASSUMPTIONS_DIGEST: MaybePruned<Assumptions> = MaybePruned::Pruned(Di
 /// information leakage through the post-state digest.
 static mut MEMORY_IMAGE_ENTROPY: [u32; 4] = [0u32; 4];
 
+/// The last key committed with [commit_kv], used to check that keys are committed in order.
+static mut LAST_KV_KEY: Option<String> = None;
+
 pub(crate) fn init() {
     unsafe {
         HASHER.set(Sha256::new()).unwrap();
@@ -161,6 +168,17 @@ pub fn syscall(syscall: SyscallName, to_host: &[u8], from_host: &mut [u32]) -> s
     }
 }
 
+/// Return the number of assumptions the host registered with
+/// `ExecutorEnvBuilder::add_assumption`.
+///
+/// This allows a guest to run exactly as many [verify] or [verify_integrity] calls as there are
+/// assumptions available. The count is only a hint from the host; each verification is still
+/// checked independently.
+pub fn assumption_count() -> usize {
+    let syscall::Return(count, _) = syscall(SYS_ASSUMPTION_COUNT, &[], &mut []);
+    count as usize
+}
+
 /// Verify there exists a receipt for an execution with `image_id` and `journal`.
 ///
 /// Calling this function in the guest is logically equivalent to verifying a receipt with the same
@@ -360,6 +378,50 @@ pub fn send_recv_slice<T: Pod, U: Pod>(syscall_name: SyscallName, to_host: &[T])
     &bytemuck::cast_slice(from_host_buf)[..nelem as usize]
 }
 
+/// Fetch a content-addressed blob from the host by its SHA-256 digest.
+///
+/// The host supplies the blob using the provider registered with
+/// `ExecutorEnvBuilder::blob_provider`. The guest hashes the returned bytes and
+/// panics if they do not match `digest`, so the contents are bound to the
+/// digest in the proof.
+pub fn fetch_blob(digest: &Digest) -> &'static [u8] {
+    let blob: &[u8] = send_recv_slice(SYS_FETCH_BLOB, digest.as_bytes());
+    assert_eq!(&blob.digest(), digest, "fetched blob does not match digest");
+    blob
+}
+
+/// Read the next chunk of at most `max_len` bytes of chunked input.
+///
+/// The host supplies the data registered with
+/// `ExecutorEnvBuilder::chunked_input` and advances its cursor past the
+/// returned bytes, so consecutive calls never return the same data. An empty
+/// slice is returned once the input is exhausted.
+pub fn read_chunk(max_len: u32) -> &'static [u8] {
+    send_recv_slice(SYS_READ_CHUNK, &[max_len])
+}
+
+/// Read the identifier of this run, as set by `ExecutorEnvBuilder::run_id`.
+///
+/// Panics if the host did not set a run ID.
+pub fn run_id() -> [u8; 16] {
+    let id: &[u8] = send_recv_slice::<u8, u8>(SYS_RUN_ID, &[]);
+    id.try_into().expect("host did not provide a valid run ID")
+}
+
+/// Read the configuration set by `ExecutorEnvBuilder::config` and deserialize
+/// it.
+///
+/// The configuration is read from its own channel, so it can be read at any
+/// point and does not consume any data written to stdin. Each call returns a
+/// fresh copy of the same value.
+///
+/// Panics if the host did not provide a configuration that deserializes as
+/// `T`.
+pub fn config<T: DeserializeOwned>() -> T {
+    let bytes: &[u8] = send_recv_slice::<u8, u8>(SYS_CONFIG, &[]);
+    crate::serde::from_slice(bytes).expect("host did not provide a valid config")
+}
+
 /// Read private data from the STDIN of the zkVM and deserializes it.
 ///
 /// This function operates on every [`DeserializeOwned`] type, so you can
@@ -505,6 +567,62 @@ pub fn commit<T: Serialize>(data: &T) {
     journal().write(data)
 }
 
+/// Commit a keyed value to the journal.
+///
+/// A journal made up only of keyed values can be read back on the host as a
+/// map with `Receipt::journal_map`. Keys must be committed in strictly
+/// increasing order, so that a given map always produces the same journal
+/// and therefore the same claim; the host rejects journals that break this.
+/// To commit keys in any order, collect them into a map and use [commit_map].
+///
+/// # Panics
+///
+/// Panics if `key` is not greater than the key of the previous call.
+///
+/// # Example
+///
+/// ```no_run
+/// use risc0_zkvm::guest::env;
+///
+/// env::commit_kv("count", &3u32.to_le_bytes());
+/// env::commit_kv("name", b"alice");
+/// ```
+pub fn commit_kv(key: &str, value: &[u8]) {
+    // SAFETY: Single threaded and this function is not reentrant.
+    let last_key = unsafe { &mut LAST_KV_KEY };
+    if let Some(last) = last_key {
+        assert!(
+            key > last.as_str(),
+            "env::commit_kv: key {key:?} is not greater than the previous key {last:?}"
+        );
+    }
+    *last_key = Some(key.into());
+    commit(&(key, value))
+}
+
+/// Commit every entry of the given map to the journal, as with [commit_kv].
+///
+/// Entries are committed in key order, so the journal can be read back on the
+/// host with `Receipt::journal_map` regardless of the order the map was built in.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::BTreeMap;
+///
+/// use risc0_zkvm::guest::env;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("name".to_string(), b"alice".to_vec());
+/// map.insert("count".to_string(), 3u32.to_le_bytes().to_vec());
+/// env::commit_map(&map);
+/// ```
+pub fn commit_map(map: &BTreeMap<String, Vec<u8>>) {
+    for (key, value) in map {
+        commit_kv(key, value);
+    }
+}
+
 /// Commit the given slice to the journal.
 ///
 /// Data in the journal is included in the receipt and is available to the
@@ -543,6 +661,17 @@ pub fn cycle_count() -> u64 {
     sys_cycle_count()
 }
 
+/// Emit a diagnostic event to the host.
+///
+/// Events are collected in order into `Session::events` on the host. Unlike
+/// data written with [commit], events are not part of the journal and are not
+/// covered by the proof, so the host must not rely on them being correct.
+pub fn emit_event(event: &[u8]) {
+    unsafe {
+        sys_emit_event(event.as_ptr(), event.len());
+    }
+}
+
 /// Print a message to the debug console.
 pub fn log(msg: &str) {
     let msg = msg.as_bytes();
@@ -573,6 +702,15 @@ pub fn stdin() -> FdReader {
     FdReader::new(fileno::STDIN)
 }
 
+/// Return a reader for an input channel written by the host with
+/// `ExecutorEnvBuilder::write_to_channel`.
+///
+/// Channels are numbered from [fileno::FIRST_CHANNEL]; lower numbers are
+/// reserved for the system.
+pub fn channel(channel: u32) -> FdReader {
+    FdReader::new(channel)
+}
+
 /// Reads and deserializes objects
 pub trait Read {
     /// Read data from the host.
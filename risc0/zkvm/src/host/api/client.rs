@@ -392,6 +392,8 @@ impl Client {
         env: &ExecutorEnv<'_>,
         binary: pb::api::Asset,
     ) -> Result<pb::api::ExecutorEnv> {
+        // The server cannot call back for lazy assumptions, so produce them up front.
+        env.assumptions.borrow_mut().resolve_pending()?;
         Ok(pb::api::ExecutorEnv {
             binary: Some(binary),
             env_vars: env.env_vars.clone(),
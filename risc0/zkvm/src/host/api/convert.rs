@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use anyhow::{anyhow, bail, Result};
 use prost::{Message, Name};
@@ -28,7 +28,7 @@ use crate::{
         recursion::SuccinctReceipt,
     },
     Assumptions, ExitCode, Journal, MaybePruned, Output, ProveInfo, ProverOpts, Receipt,
-    ReceiptClaim, ReceiptKind, SessionStats, TraceEvent,
+    ReceiptClaim, ReceiptKind, SegmentProveStats, SessionStats, TraceEvent,
 };
 
 mod ver {
@@ -196,17 +196,28 @@ impl From<pb::api::GenericError> for anyhow::Error {
     }
 }
 
+fn receipt_kind_from_pb(value: i32) -> ReceiptKind {
+    match value {
+        0 => ReceiptKind::Composite,
+        1 => ReceiptKind::Succinct,
+        2 => ReceiptKind::Groth16,
+        value => panic!("Unknown receipt kind number: {value}"),
+    }
+}
+
 impl From<pb::api::ProverOpts> for ProverOpts {
     fn from(opts: pb::api::ProverOpts) -> Self {
         Self {
             hashfn: opts.hashfn,
             prove_guest_errors: opts.prove_guest_errors,
-            receipt_kind: match opts.receipt_kind {
-                0 => ReceiptKind::Composite,
-                1 => ReceiptKind::Succinct,
-                2 => ReceiptKind::Compact,
-                value => panic!("Unknown receipt kind number: {value}"),
-            },
+            receipt_kind: receipt_kind_from_pb(opts.receipt_kind),
+            retain_claim_tree: opts.retain_claim_tree,
+            wall_clock_timeout: opts.wall_clock_timeout_ms.map(Duration::from_millis),
+            stop_after: opts.stop_after.map(receipt_kind_from_pb),
+            continue_on_segment_error: opts.continue_on_segment_error,
+            segment_po2: opts.segment_po2,
+            max_total_cycles: opts.max_total_cycles,
+            lift_cache: None,
         }
     }
 }
@@ -217,6 +228,14 @@ impl From<ProverOpts> for pb::api::ProverOpts {
             hashfn: opts.hashfn,
             prove_guest_errors: opts.prove_guest_errors,
             receipt_kind: opts.receipt_kind as i32,
+            retain_claim_tree: opts.retain_claim_tree,
+            wall_clock_timeout_ms: opts
+                .wall_clock_timeout
+                .map(|timeout| timeout.as_millis() as u64),
+            stop_after: opts.stop_after.map(|kind| kind as i32),
+            continue_on_segment_error: opts.continue_on_segment_error,
+            segment_po2: opts.segment_po2,
+            max_total_cycles: opts.max_total_cycles,
         }
     }
 }
@@ -270,11 +289,31 @@ impl TryFrom<pb::core::SessionStats> for SessionStats {
     }
 }
 
+impl From<SegmentProveStats> for pb::core::SegmentProveStats {
+    fn from(value: SegmentProveStats) -> Self {
+        Self {
+            witness_peak_bytes: value.witness_peak_bytes,
+            duration_ns: value.duration.as_nanos() as u64,
+        }
+    }
+}
+
+impl From<pb::core::SegmentProveStats> for SegmentProveStats {
+    fn from(value: pb::core::SegmentProveStats) -> Self {
+        Self {
+            witness_peak_bytes: value.witness_peak_bytes,
+            duration: Duration::from_nanos(value.duration_ns),
+        }
+    }
+}
+
 impl From<ProveInfo> for pb::core::ProveInfo {
     fn from(value: ProveInfo) -> Self {
         Self {
             receipt: Some(value.receipt.into()),
             stats: Some(value.stats.into()),
+            segment_stats: value.segment_stats.into_iter().map(Into::into).collect(),
+            proving_time_ns: value.proving_time.as_nanos() as u64,
         }
     }
 }
@@ -286,6 +325,8 @@ impl TryFrom<pb::core::ProveInfo> for ProveInfo {
         Ok(Self {
             receipt: value.receipt.ok_or(malformed_err())?.try_into()?,
             stats: value.stats.ok_or(malformed_err())?.try_into()?,
+            segment_stats: value.segment_stats.into_iter().map(Into::into).collect(),
+            proving_time: Duration::from_nanos(value.proving_time_ns),
         })
     }
 }
@@ -296,6 +337,7 @@ impl From<Receipt> for pb::core::Receipt {
             version: Some(ver::RECEIPT),
             inner: Some(value.inner.into()),
             journal: value.journal.bytes,
+            metadata: value.metadata.into_iter().collect(),
         }
     }
 }
@@ -311,6 +353,7 @@ impl TryFrom<pb::core::Receipt> for Receipt {
         Ok(Self {
             inner: value.inner.ok_or(malformed_err())?.try_into()?,
             journal: Journal::new(value.journal),
+            metadata: value.metadata.into_iter().collect(),
         })
     }
 }
@@ -390,6 +433,7 @@ impl TryFrom<pb::core::SuccinctReceipt> for SuccinctReceipt {
             seal,
             control_id: value.control_id.ok_or(malformed_err())?.try_into()?,
             claim: value.claim.ok_or(malformed_err())?.try_into()?,
+            claim_tree: None,
         })
     }
 }
@@ -15,7 +15,7 @@
 //! This module defines the [ExecutorEnv] and [ExecutorEnvBuilder].
 
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::HashMap,
     io::{BufRead, BufReader, Cursor, Read, Write},
     mem,
@@ -24,11 +24,15 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, ensure, Result};
 use bytemuck::Pod;
 use bytes::Bytes;
 use risc0_zkp::core::digest::Digest;
-use risc0_zkvm_platform::{self, fileno};
+use risc0_zkvm_platform::{
+    self, fileno,
+    syscall::nr::{SYS_CONFIG, SYS_FETCH_BLOB, SYS_READ_CHUNK, SYS_RUN_ID},
+    WORD_SIZE,
+};
 use serde::Serialize;
 use tempfile::TempDir;
 
@@ -38,7 +42,8 @@ use crate::{
         slice_io::{slice_io_from_fn, SliceIo, SliceIoTable},
     },
     serde::to_vec,
-    Assumption, TraceCallback,
+    sha::Digestible,
+    Assumption, MaybePruned, Receipt, TraceCallback,
 };
 
 /// A builder pattern used to construct an [ExecutorEnv].
@@ -48,15 +53,76 @@ pub struct ExecutorEnvBuilder<'a> {
 }
 
 /// Container for assumptions in the executor environment.
-#[derive(Debug, Default)]
-pub(crate) struct Assumptions {
+#[derive(Default)]
+pub(crate) struct Assumptions<'a> {
     pub(crate) cached: Vec<Assumption>,
+    // Receipts added with `add_assumption_lazy` that have not been produced yet, in the order
+    // they were added.
+    pub(crate) pending: Vec<Box<dyn FnOnce() -> Result<Receipt> + 'a>>,
+    // Receipts added with `add_assumption_by_digest`, keyed by claim digest.
+    pub(crate) deferred: HashMap<Digest, DeferredReceipt<'a>>,
     // An ordered list of assumptions accessed during execution.
     // Each time an assumption is used, it is cloned and pushed to the head of the list.
     #[cfg(feature = "prove")]
     pub(crate) accessed: Vec<Assumption>,
 }
 
+impl<'a> Assumptions<'a> {
+    /// Produce every pending receipt and add it to the cached assumptions.
+    pub(crate) fn resolve_pending(&mut self) -> Result<()> {
+        for receipt in mem::take(&mut self.pending) {
+            self.cached.push(Assumption::Proven(receipt()?));
+        }
+        Ok(())
+    }
+
+    /// Return the first assumption for which `matches` returns a value, along with that value.
+    ///
+    /// The cached assumptions are searched first. If none of them match, pending receipts are
+    /// produced one at a time, in the order they were added, until one matches; receipts after
+    /// the match stay pending.
+    pub(crate) fn find<T>(
+        &mut self,
+        mut matches: impl FnMut(&Assumption) -> Result<Option<T>>,
+    ) -> Result<Option<(Assumption, T)>> {
+        for assumption in self.cached.iter() {
+            if let Some(value) = matches(assumption)? {
+                return Ok(Some((assumption.clone(), value)));
+            }
+        }
+        while !self.pending.is_empty() {
+            let assumption = Assumption::Proven(self.pending.remove(0)()?);
+            let value = matches(&assumption)?;
+            self.cached.push(assumption.clone());
+            if let Some(value) = value {
+                return Ok(Some((assumption, value)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// An assumption added with [ExecutorEnvBuilder::add_assumption_by_digest],
+/// along with the function that fetches its receipt.
+pub(crate) struct DeferredReceipt<'a> {
+    claim_digest: Digest,
+    fetcher: Box<dyn Fn(Digest) -> Result<Receipt> + 'a>,
+}
+
+impl<'a> DeferredReceipt<'a> {
+    /// Call the fetcher and check that the receipt proves the claim digest.
+    pub(crate) fn fetch(&self) -> Result<Receipt> {
+        let receipt = (self.fetcher)(self.claim_digest)?;
+        let digest = receipt.claim()?.digest();
+        ensure!(
+            digest == self.claim_digest,
+            "fetched receipt has claim digest {digest}, expected {}",
+            self.claim_digest
+        );
+        Ok(receipt)
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub enum SegmentPath {
@@ -86,11 +152,17 @@ pub struct ExecutorEnv<'a> {
     pub(crate) posix_io: Rc<RefCell<PosixIo<'a>>>,
     pub(crate) slice_io: Rc<RefCell<SliceIoTable<'a>>>,
     pub(crate) input: Vec<u8>,
+    pub(crate) channels: HashMap<u32, Vec<u8>>,
     pub(crate) trace: Vec<Rc<RefCell<dyn TraceCallback + 'a>>>,
-    pub(crate) assumptions: Rc<RefCell<Assumptions>>,
+    pub(crate) journal_callback: Option<Rc<RefCell<dyn FnMut(&[u8]) + 'a>>>,
+    pub(crate) assumptions: Rc<RefCell<Assumptions<'a>>>,
     pub(crate) segment_path: Option<SegmentPath>,
     pub(crate) pprof_out: Option<PathBuf>,
+    pub(crate) pc_stats: bool,
     pub(crate) input_digest: Option<Digest>,
+    pub(crate) verify_input_digest: bool,
+    pub(crate) break_on_write: Option<u32>,
+    pub(crate) fixed_segment_cycles: Option<u64>,
 }
 
 impl<'a> ExecutorEnv<'a> {
@@ -132,6 +204,13 @@ impl<'a> ExecutorEnvBuilder<'a> {
                 .with_read_fd(fileno::STDIN, reader);
         }
 
+        for (fd, data) in mem::take(&mut inner.channels) {
+            inner
+                .posix_io
+                .borrow_mut()
+                .with_read_fd(fd, Cursor::new(data));
+        }
+
         if inner.pprof_out.is_none() {
             if let Ok(env_var) = std::env::var("RISC0_PPROF_OUT") {
                 inner.pprof_out = Some(env_var.into());
@@ -150,6 +229,31 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Split segments after a fixed number of instruction cycles.
+    ///
+    /// By default, a segment is split when its instruction and paging cycles
+    /// fill the segment limit, so boundaries can shift when memory access
+    /// patterns change. With this option, each segment ends before the first
+    /// instruction that would take it past `cycles` instruction cycles,
+    /// regardless of paging. Execution fails if such a segment does not fit
+    /// in the limit set by [ExecutorEnvBuilder::segment_limit_po2].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::ExecutorEnv;
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .segment_limit_po2(20)
+    ///     .fixed_segment_cycles(512 * 1024)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn fixed_segment_cycles(&mut self, cycles: u64) -> &mut Self {
+        self.inner.fixed_segment_cycles = Some(cycles);
+        self
+    }
+
     /// Set a session limit, specified in number of cycles.
     ///
     /// # Example
@@ -167,6 +271,27 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Stop execution after the first store by the guest to the word at `addr`.
+    ///
+    /// The resulting [crate::Session] includes the store, ends with
+    /// [crate::ExitCode::SessionLimit], and has `breakpoint` set. This is a
+    /// debugging aid; such a session does not prove a complete execution.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::ExecutorEnv;
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .break_on_write(0x0010_0000)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn break_on_write(&mut self, addr: u32) -> &mut Self {
+        self.inner.break_on_write = Some(addr);
+        self
+    }
+
     /// Add environment variables to the guest environment.
     ///
     /// # Example
@@ -280,7 +405,56 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Return the length, in words, of the input written to stdin so far.
+    ///
+    /// This is the word offset at which the guest will start reading the next
+    /// value passed to [ExecutorEnvBuilder::write], which is useful to correlate
+    /// guest-side reads with host-side writes when debugging input framing.
+    /// Input written with [ExecutorEnvBuilder::write_slice] that does not fill a
+    /// whole word is rounded up. Input from [ExecutorEnvBuilder::stdin] is not
+    /// counted.
+    pub fn input_len(&self) -> usize {
+        self.inner.input.len().div_ceil(WORD_SIZE)
+    }
+
+    /// Write input data to a separate input channel of the zkVM guest.
+    ///
+    /// Each channel is an independent stream that the guest reads from the file
+    /// descriptor of the same number, e.g. with `env::channel(channel).read()`.
+    /// Channel numbers below [fileno::FIRST_CHANNEL] are reserved for the
+    /// system; [ExecutorEnvBuilder::write] always writes to stdin.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::ExecutorEnv;
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .write_to_channel(10, &"first").unwrap()
+    ///     .write_to_channel(11, &"second").unwrap()
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn write_to_channel<T: Serialize>(&mut self, channel: u32, data: &T) -> Result<&mut Self> {
+        ensure!(
+            channel >= fileno::FIRST_CHANNEL,
+            "input channel {channel} is reserved for the system; channels start at {}",
+            fileno::FIRST_CHANNEL
+        );
+        let words = to_vec(data)?;
+        self.inner
+            .channels
+            .entry(channel)
+            .or_default()
+            .extend_from_slice(bytemuck::cast_slice(&words));
+        Ok(self)
+    }
+
     /// Add a posix-style standard input.
+    ///
+    /// The reader is pulled from lazily as the guest reads from stdin, so it can stream from a
+    /// file or socket, and its EOF is reported to the guest as a zero-length read. Data added with
+    /// [ExecutorEnvBuilder::write] or [ExecutorEnvBuilder::write_slice] replaces this reader.
     pub fn stdin(&mut self, reader: impl Read + 'a) -> &mut Self {
         self.read_fd(fileno::STDIN, BufReader::new(reader))
     }
@@ -329,6 +503,120 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Add a provider for content-addressed blobs requested by the guest.
+    ///
+    /// When the guest calls `env::fetch_blob` with a SHA-256 digest, the
+    /// provider is called with that digest and must return the matching bytes.
+    /// Execution fails if the provider returns `None` or if the returned bytes
+    /// do not hash to the requested digest.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::{sha::Digestible, ExecutorEnv};
+    ///
+    /// let blob = b"hello world".to_vec();
+    /// let digest = blob.digest();
+    /// let env = ExecutorEnv::builder()
+    ///     .blob_provider(move |x| (x == digest).then(|| blob.clone()))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn blob_provider(
+        &mut self,
+        provider: impl Fn(Digest) -> Option<Vec<u8>> + 'a,
+    ) -> &mut Self {
+        self.io_callback(SYS_FETCH_BLOB, move |buf| {
+            let digest = Digest::try_from(buf.as_ref())?;
+            let blob = provider(digest)
+                .ok_or_else(|| anyhow!("no blob available for digest: {digest}"))?;
+            ensure!(
+                blob.as_slice().digest() == digest,
+                "blob provided for digest {digest} does not match its content"
+            );
+            Ok(blob.into())
+        })
+    }
+
+    /// Provide input to the guest that it reads in chunks via `env::read_chunk`.
+    ///
+    /// A cursor into `data` is kept by the host for the whole execution, so
+    /// each call returns the bytes following the previous chunk, even when the
+    /// calls happen in different segments. Once all of `data` has been
+    /// consumed, the guest receives empty chunks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::ExecutorEnv;
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .chunked_input(b"some large input".to_vec())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn chunked_input(&mut self, data: impl Into<Bytes>) -> &mut Self {
+        let data: Bytes = data.into();
+        let cursor = Cell::new(0);
+        self.io_callback(SYS_READ_CHUNK, move |buf| {
+            let max_len: [u8; 4] = buf.as_ref().try_into()?;
+            let start = cursor.get();
+            let end = data.len().min(start + u32::from_le_bytes(max_len) as usize);
+            cursor.set(end);
+            Ok(data.slice(start..end))
+        })
+    }
+
+    /// Set an identifier for this run that the guest can read with
+    /// `env::run_id`.
+    ///
+    /// This is intended for correlating host and guest logs. Like any other
+    /// input, it is chosen by the host and is not authenticated by the proof
+    /// unless the guest commits it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::ExecutorEnv;
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .run_id([7u8; 16])
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn run_id(&mut self, id: [u8; 16]) -> &mut Self {
+        self.io_callback(SYS_RUN_ID, move |_| Ok(Bytes::copy_from_slice(&id)))
+    }
+
+    /// Set a configuration value that the guest can read with `env::config`.
+    ///
+    /// The configuration is served on a dedicated channel, separate from
+    /// stdin, so reading it does not interfere with data written by
+    /// [ExecutorEnvBuilder::write]. Like any other input, it is not
+    /// authenticated by the proof unless the guest commits it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::ExecutorEnv;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     threshold: u32,
+    /// }
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .config(&Config { threshold: 10 })
+    ///     .unwrap()
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn config<T: Serialize>(&mut self, config: &T) -> Result<&mut Self> {
+        let bytes = Bytes::copy_from_slice(bytemuck::cast_slice(&to_vec(config)?));
+        Ok(self.io_callback(SYS_CONFIG, move |_| Ok(bytes.clone())))
+    }
+
     /// Add an [Assumption] to the [ExecutorEnv], for use in [composition].
     ///
     /// During execution, when the guest calls `env::verify` or
@@ -349,12 +637,91 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Add a proven [Assumption] whose [crate::Receipt] is only produced if the
+    /// guest needs it.
+    ///
+    /// The closure is only called when the guest calls `env::verify` or
+    /// `env::verify_integrity` for a claim that no other assumption matches, so
+    /// an expensive assumption is never proven for an execution that does not
+    /// use it. Lazy assumptions are produced one at a time, in the order they
+    /// were added, until one matches the claim; those added after it are not
+    /// produced for that call. Provers that send assumptions to a remote
+    /// service call every closure before execution starts.
+    pub fn add_assumption_lazy(
+        &mut self,
+        receipt: impl FnOnce() -> Result<Receipt> + 'a,
+    ) -> &mut Self {
+        self.inner
+            .assumptions
+            .borrow_mut()
+            .pending
+            .push(Box::new(receipt));
+        self
+    }
+
+    /// Add an [Assumption] by its [crate::ReceiptClaim] digest, with a function
+    /// that fetches the [crate::Receipt] proving it.
+    ///
+    /// Execution treats the assumption as unresolved, so it can be used with
+    /// `env::verify_integrity`, but not with `env::verify`, which needs the full
+    /// claim. Adding the same digest again is a no-op.
+    ///
+    /// Only the local prover calls the fetcher, when it proves this environment:
+    /// after execution, it fetches the receipt for each of these assumptions
+    /// the guest used, once per digest, and resolves the assumption with it.
+    /// Executing without proving never calls it, and a `Session` from
+    /// `ExecutorImpl::run` keeps these assumptions unresolved. The remote API
+    /// prover and `ReproBundle` do not run the fetcher either, so they see
+    /// these assumptions as unresolved, pruned claims, and the resulting
+    /// receipt is conditional on them.
+    pub fn add_assumption_by_digest(
+        &mut self,
+        claim_digest: Digest,
+        fetcher: impl Fn(Digest) -> Result<Receipt> + 'a,
+    ) -> &mut Self {
+        let mut assumptions = self.inner.assumptions.borrow_mut();
+        if !assumptions.deferred.contains_key(&claim_digest) {
+            let receipt = DeferredReceipt {
+                claim_digest,
+                fetcher: Box::new(fetcher),
+            };
+            assumptions.deferred.insert(claim_digest, receipt);
+            assumptions
+                .cached
+                .push(Assumption::Unresolved(MaybePruned::Pruned(claim_digest)));
+        }
+        drop(assumptions);
+        self
+    }
+
     /// Add a callback handler for raw trace messages.
     pub fn trace_callback(&mut self, callback: impl TraceCallback + 'a) -> &mut Self {
         self.inner.trace.push(Rc::new(RefCell::new(callback)));
         self
     }
 
+    /// Add a callback that receives the bytes the guest commits to the journal, as they are
+    /// written.
+    ///
+    /// Concatenating the bytes from every call yields the journal of the resulting
+    /// [crate::Session], unless the guest exits without a journal. Only one callback is kept;
+    /// calling this again replaces it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::ExecutorEnv;
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .on_journal_write(|bytes| println!("journal: {} bytes", bytes.len()))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn on_journal_write(&mut self, callback: impl FnMut(&[u8]) + 'a) -> &mut Self {
+        self.inner.journal_callback = Some(Rc::new(RefCell::new(callback)));
+        self
+    }
+
     /// Set the path where segments will be stored.
     pub fn segment_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
         self.inner.segment_path = Some(SegmentPath::Path(path.as_ref().to_path_buf()));
@@ -367,9 +734,32 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Count the cycles spent at each instruction address, to be read with
+    /// [Session::pc_stats](crate::Session::pc_stats).
+    ///
+    /// Unlike [ExecutorEnvBuilder::enable_profiler], this does not need the ELF
+    /// and does not write a file. It only observes execution, so it does not
+    /// change the segments that are produced. Disabled by default.
+    pub fn enable_profiling(&mut self) -> &mut Self {
+        self.inner.pc_stats = true;
+        self
+    }
+
     /// Set the input digest.
     pub fn input_digest(&mut self, digest: Digest) -> &mut Self {
         self.inner.input_digest = Some(digest);
         self
     }
+
+    /// Check that the digest set with [ExecutorEnvBuilder::input_digest] is the
+    /// SHA-256 digest of the input written with [ExecutorEnvBuilder::write] and
+    /// [ExecutorEnvBuilder::write_slice].
+    ///
+    /// When enabled, running the executor returns an error on a mismatch
+    /// instead of executing the guest. Input from [ExecutorEnvBuilder::stdin]
+    /// is not part of the digest. Disabled by default.
+    pub fn verify_input_digest(&mut self, verify: bool) -> &mut Self {
+        self.inner.verify_input_digest = verify;
+        self
+    }
 }
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
 use bonsai_sdk::alpha::Client;
@@ -63,6 +63,7 @@ impl Prover for BonsaiProver {
         let input_id = client.upload_input(env.input)?;
 
         // upload receipts
+        env.assumptions.borrow_mut().resolve_pending()?;
         let mut receipts_ids: Vec<String> = vec![];
         for assumption in &env.assumptions.borrow().cached {
             let serialized_receipt = match assumption {
@@ -78,6 +79,7 @@ impl Prover for BonsaiProver {
         // retrieve the receipt.
         let session = client.create_session(image_id_hex, input_id, receipts_ids)?;
         tracing::debug!("Bonsai proving SessionID: {}", session.uuid);
+        let start = Instant::now();
 
         let succinct_prove_info = loop {
             // The session has already been started in the executor. Poll bonsai to check if
@@ -124,6 +126,8 @@ impl Prover for BonsaiProver {
                         total_cycles: stats.total_cycles,
                         user_cycles: stats.cycles,
                     },
+                    segment_stats: Vec::new(),
+                    proving_time: start.elapsed(),
                 };
             } else {
                 bail!(
@@ -141,7 +145,7 @@ impl Prover for BonsaiProver {
                 return Ok(succinct_prove_info);
             }
             // If they requested a compact receipts, we need to continue.
-            ReceiptKind::Compact => {}
+            ReceiptKind::Groth16 => {}
         }
 
         // Request that Bonsai compress further, to Groth16.
@@ -196,6 +200,8 @@ impl Prover for BonsaiProver {
         Ok(ProveInfo {
             receipt: compact_receipt,
             stats: succinct_prove_info.stats,
+            segment_stats: succinct_prove_info.segment_stats,
+            proving_time: start.elapsed(),
         })
     }
 
@@ -206,7 +212,7 @@ impl Prover for BonsaiProver {
             | (InnerReceipt::Succinct(_), ReceiptKind::Composite | ReceiptKind::Succinct)
             | (
                 InnerReceipt::Compact(_),
-                ReceiptKind::Composite | ReceiptKind::Succinct | ReceiptKind::Compact,
+                ReceiptKind::Composite | ReceiptKind::Succinct | ReceiptKind::Groth16,
             ) => Ok(receipt.clone()),
             // Compression is always a no-op in dev mode
             (InnerReceipt::Fake { .. }, _) => {
@@ -221,7 +227,7 @@ impl Prover for BonsaiProver {
             (_, ReceiptKind::Succinct) => {
                 bail!("BonsaiProver does not support compression on existing receipts");
             }
-            (_, ReceiptKind::Compact) => {
+            (_, ReceiptKind::Groth16) => {
                 // Caller is requesting a CompactReceipt. Provide a hint on how to get one.
                 bail!([
                     "BonsaiProver does not support compression on existing receipts",
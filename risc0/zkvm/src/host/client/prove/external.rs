@@ -130,7 +130,7 @@ impl Prover for ExternalProver {
             | (InnerReceipt::Succinct(_), ReceiptKind::Composite | ReceiptKind::Succinct)
             | (
                 InnerReceipt::Compact(_),
-                ReceiptKind::Composite | ReceiptKind::Succinct | ReceiptKind::Compact,
+                ReceiptKind::Composite | ReceiptKind::Succinct | ReceiptKind::Groth16,
             ) => Ok(receipt.clone()),
             // Compression is always a no-op in dev mode
             (InnerReceipt::Fake { .. }, _) => {
@@ -147,9 +147,10 @@ impl Prover for ExternalProver {
                         &client, opts, composite,
                     )?),
                     journal: receipt.journal.clone(),
+                    metadata: receipt.metadata.clone(),
                 })
             }
-            (_, ReceiptKind::Compact) => {
+            (_, ReceiptKind::Groth16) => {
                 // TODO(#1760) Support compression to compact receipt in client/server API.
                 bail!("ExternalProver does not support compression to CompactReceipt");
             }
@@ -17,14 +17,22 @@ pub(crate) mod external;
 #[cfg(feature = "prove")]
 pub(crate) mod local;
 
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use anyhow::Result;
+use risc0_zkp::core::digest::Digest;
 use serde::{Deserialize, Serialize};
 
 use self::{bonsai::BonsaiProver, external::ExternalProver};
 use crate::{
-    host::prove_info::ProveInfo, is_dev_mode, ExecutorEnv, Receipt, SessionInfo, VerifierContext,
+    host::prove_info::ProveInfo, is_dev_mode, sha::Digestible, ExecutorEnv, Receipt,
+    SegmentReceipt, SessionInfo, SuccinctReceipt, VerifierContext,
 };
 
 /// A Prover can execute a given ELF binary and produce a
@@ -135,10 +143,94 @@ pub struct ProverOpts {
     pub prove_guest_errors: bool,
     /// Kind of receipt to be generated by the prover.
     pub receipt_kind: ReceiptKind,
+    /// When true, compressing a composite receipt into a succinct receipt retains the tree of
+    /// claims that were folded together. See [crate::SuccinctReceipt::claim_tree].
+    #[serde(default)]
+    pub retain_claim_tree: bool,
+    /// When set, proving a session stops once this much wall-clock time has elapsed and fails
+    /// with [crate::ProveError::TimedOut], carrying the segment receipts proven so far.
+    #[serde(default)]
+    pub wall_clock_timeout: Option<Duration>,
+    /// When set, proving stops once a receipt of this kind is produced, even if
+    /// [ProverOpts::receipt_kind] requests a more compressed kind. This is a debugging aid for
+    /// inspecting intermediate receipts.
+    #[serde(default)]
+    pub stop_after: Option<ReceiptKind>,
+    /// When true, proving a session attempts every segment even after one fails, and then fails
+    /// with [crate::ProveError::SegmentsFailed] listing every failure. When false, proving stops
+    /// at the first failing segment.
+    #[serde(default)]
+    pub continue_on_segment_error: bool,
+    /// When set, every segment is proven with a trace of `2^segment_po2` cycles rather than the
+    /// size it was given during execution.
+    ///
+    /// This lets a single [crate::Session] be proven at several segment sizes, but segments can
+    /// only be padded to a larger size; proving fails if a segment was executed with a larger
+    /// po2, in which case the session must be re-executed with a smaller
+    /// [crate::ExecutorEnvBuilder::segment_limit_po2]. The value must be at least
+    /// [risc0_zkp::MIN_CYCLES_PO2] and less than [risc0_zkp::MAX_CYCLES_PO2].
+    #[serde(default)]
+    pub segment_po2: Option<u32>,
+    /// When set, proving a session fails with [crate::ProveError::CycleLimitExceeded] instead of
+    /// proving a segment that would take the total across proven segments past this many cycles.
+    ///
+    /// Each segment counts the full `2^po2` cycles of its trace, padding included, as that is
+    /// what determines the cost of proving it.
+    #[serde(default)]
+    pub max_total_cycles: Option<u64>,
+    /// When set, compressing a composite receipt reuses lifted segment receipts from this cache,
+    /// and adds the ones it lifts. The cache is not serialized.
+    #[serde(skip)]
+    pub lift_cache: Option<Arc<LiftCache>>,
+}
+
+/// A cache of lifted segment receipts that can be shared between compressions.
+///
+/// Lifting every segment is the bulk of the work of compressing a composite receipt. Sharing a
+/// cache through [ProverOpts::with_lift_cache] lets a composite receipt be compressed several
+/// times while lifting each segment only once. Receipts are keyed by the digest of the segment's
+/// claim.
+#[derive(Default)]
+pub struct LiftCache {
+    receipts: Mutex<HashMap<Digest, SuccinctReceipt>>,
+}
+
+impl LiftCache {
+    /// Construct an empty [LiftCache].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of lifted receipts in the cache.
+    pub fn len(&self) -> usize {
+        self.receipts.lock().unwrap().len()
+    }
+
+    /// True if the cache holds no lifted receipts.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the cached lift of `receipt`, or lift it with `lift` and cache the result.
+    #[cfg(feature = "prove")]
+    pub(crate) fn get_or_lift(
+        &self,
+        receipt: &SegmentReceipt,
+        lift: impl FnOnce(&SegmentReceipt) -> Result<SuccinctReceipt>,
+    ) -> Result<SuccinctReceipt> {
+        let key = receipt.claim.digest();
+        if let Some(lifted) = self.receipts.lock().unwrap().get(&key) {
+            return Ok(lifted.clone());
+        }
+        // Lift without holding the lock, as lifting is slow.
+        let lifted = lift(receipt)?;
+        self.receipts.lock().unwrap().insert(key, lifted.clone());
+        Ok(lifted)
+    }
 }
 
 /// An enumeration of receipt kinds that can be requested to be generated.
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ReceiptKind {
     /// Request that a [CompositeReceipt][crate::CompositeReceipt] be generated.
@@ -150,11 +242,19 @@ pub enum ReceiptKind {
     ///
     /// Succinct receipts are constant in size, with respect to the execution length.
     Succinct,
-    /// Request that a [CompactReceipt][crate::CompactReceipt] be generated.
+    /// Request that a Groth16 [CompactReceipt][crate::CompactReceipt] be generated.
     ///
-    /// Compact receipts are proven using Groth16, are constant in size, and are the smallest
-    /// available receipt format. A compact receipt can be serialized to a few hundred bytes.
-    Compact,
+    /// Groth16 receipts wrap a succinct receipt in a Groth16 SNARK, are constant in size, and are
+    /// the smallest available receipt format. They can be serialized to a few hundred bytes.
+    #[serde(alias = "Compact")]
+    Groth16,
+}
+
+impl ReceiptKind {
+    /// Former name of [ReceiptKind::Groth16].
+    #[deprecated(note = "use ReceiptKind::Groth16 instead")]
+    #[allow(non_upper_case_globals)]
+    pub const Compact: ReceiptKind = ReceiptKind::Groth16;
 }
 
 impl Default for ProverOpts {
@@ -165,17 +265,35 @@ impl Default for ProverOpts {
             hashfn: "poseidon2".to_string(),
             prove_guest_errors: false,
             receipt_kind: ReceiptKind::Composite,
+            retain_claim_tree: false,
+            wall_clock_timeout: None,
+            stop_after: None,
+            continue_on_segment_error: false,
+            segment_po2: None,
+            max_total_cycles: None,
+            lift_cache: None,
         }
     }
 }
 
 impl ProverOpts {
-    /// Choose the fastest prover options. May not be compatible with recursion.
+    /// Choose the fastest prover options, producing real composite receipts with `sha-256`.
+    ///
+    /// This trades receipt size and verification cost, which grow with the number of segments,
+    /// for proving speed, and suits iterating quickly during development. May not be compatible
+    /// with recursion.
     pub fn fast() -> Self {
         Self {
             hashfn: "sha-256".to_string(),
             prove_guest_errors: false,
             receipt_kind: ReceiptKind::Composite,
+            retain_claim_tree: false,
+            wall_clock_timeout: None,
+            stop_after: None,
+            continue_on_segment_error: false,
+            segment_po2: None,
+            max_total_cycles: None,
+            lift_cache: None,
         }
     }
 
@@ -185,6 +303,13 @@ impl ProverOpts {
             hashfn: "poseidon2".to_string(),
             prove_guest_errors: false,
             receipt_kind: ReceiptKind::Composite,
+            retain_claim_tree: false,
+            wall_clock_timeout: None,
+            stop_after: None,
+            continue_on_segment_error: false,
+            segment_po2: None,
+            max_total_cycles: None,
+            lift_cache: None,
         }
     }
 
@@ -194,18 +319,38 @@ impl ProverOpts {
             hashfn: "poseidon2".to_string(),
             prove_guest_errors: false,
             receipt_kind: ReceiptKind::Succinct,
+            retain_claim_tree: false,
+            wall_clock_timeout: None,
+            stop_after: None,
+            continue_on_segment_error: false,
+            segment_po2: None,
+            max_total_cycles: None,
+            lift_cache: None,
         }
     }
 
-    /// Choose the prover that enables compact, snark receipts, only supported for x86_64 linux
-    pub fn compact() -> Self {
+    /// Choose the prover that generates Groth16 receipts, only supported for x86_64 linux
+    pub fn groth16() -> Self {
         Self {
             hashfn: "poseidon2".to_string(),
             prove_guest_errors: false,
-            receipt_kind: ReceiptKind::Compact,
+            receipt_kind: ReceiptKind::Groth16,
+            retain_claim_tree: false,
+            wall_clock_timeout: None,
+            stop_after: None,
+            continue_on_segment_error: false,
+            segment_po2: None,
+            max_total_cycles: None,
+            lift_cache: None,
         }
     }
 
+    /// Former name of [ProverOpts::groth16].
+    #[deprecated(note = "use ProverOpts::groth16 instead")]
+    pub fn compact() -> Self {
+        Self::groth16()
+    }
+
     /// Return [ProverOpts] with the hashfn set to the given value.
     pub fn with_hashfn(mut self, hashfn: String) -> Self {
         self.hashfn = hashfn;
@@ -223,6 +368,48 @@ impl ProverOpts {
         self.receipt_kind = receipt_kind;
         self
     }
+
+    /// Return [ProverOpts] with retain_claim_tree set to the given value.
+    pub fn with_retain_claim_tree(mut self, retain_claim_tree: bool) -> Self {
+        self.retain_claim_tree = retain_claim_tree;
+        self
+    }
+
+    /// Return [ProverOpts] with wall_clock_timeout set to the given value.
+    pub fn with_wall_clock_timeout(mut self, wall_clock_timeout: Duration) -> Self {
+        self.wall_clock_timeout = Some(wall_clock_timeout);
+        self
+    }
+
+    /// Return [ProverOpts] with stop_after set to the given value.
+    pub fn with_stop_after(mut self, stop_after: ReceiptKind) -> Self {
+        self.stop_after = Some(stop_after);
+        self
+    }
+
+    /// Return [ProverOpts] with continue_on_segment_error set to the given value.
+    pub fn with_continue_on_segment_error(mut self, continue_on_segment_error: bool) -> Self {
+        self.continue_on_segment_error = continue_on_segment_error;
+        self
+    }
+
+    /// Return [ProverOpts] with segment_po2 set to the given value.
+    pub fn with_segment_po2(mut self, segment_po2: u32) -> Self {
+        self.segment_po2 = Some(segment_po2);
+        self
+    }
+
+    /// Return [ProverOpts] with max_total_cycles set to the given value.
+    pub fn with_max_total_cycles(mut self, max_total_cycles: u64) -> Self {
+        self.max_total_cycles = Some(max_total_cycles);
+        self
+    }
+
+    /// Return [ProverOpts] that share the given [LiftCache] between compressions.
+    pub fn with_lift_cache(mut self, lift_cache: Arc<LiftCache>) -> Self {
+        self.lift_cache = Some(lift_cache);
+        self
+    }
 }
 
 /// Return a default [Prover] based on environment variables and feature flags.
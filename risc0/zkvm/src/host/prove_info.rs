@@ -14,6 +14,13 @@
 
 //! Struct containing information about a prover's execution including the receipt.
 
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Write, time::Duration};
+
 use crate::Receipt;
 
 /// Information returned by the prover including receipt as well as other information useful for debugging
@@ -22,6 +29,13 @@ pub struct ProveInfo {
     pub receipt: Receipt,
     /// stats about cycle counts of the execution
     pub stats: SessionStats,
+    /// stats about the proving of each segment, in segment order
+    ///
+    /// This is empty when the prover does not report them, such as for remote or dev-mode
+    /// provers.
+    pub segment_stats: Vec<SegmentProveStats>,
+    /// wall-clock time spent proving, including any recursion or compression
+    pub proving_time: Duration,
 }
 
 /// Struct containing information about a prover's cycle count after running the guest program
@@ -33,3 +47,116 @@ pub struct SessionStats {
     /// User cycles run within guest
     pub user_cycles: u64,
 }
+
+/// Struct containing information about the resources used to prove a segment
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SegmentProveStats {
+    /// Peak number of bytes allocated on the host for the witness
+    ///
+    /// This is the high-water mark of the allocations made during witness generation; allocations
+    /// made inside the native circuit library are not counted.
+    pub witness_peak_bytes: u64,
+    /// Wall-clock time spent proving the segment
+    pub duration: Duration,
+}
+
+impl ProveInfo {
+    /// The wall-clock time spent proving each segment, in segment order.
+    ///
+    /// Like [ProveInfo::segment_stats], this is empty when the prover does not report it.
+    pub fn segment_timings(&self) -> Vec<Duration> {
+        self.segment_stats.iter().map(|stats| stats.duration).collect()
+    }
+
+    /// Combine the stats of this proof with those of `other`, which continues the same
+    /// computation, e.g. after resuming from a pause.
+    ///
+    /// Cycle and segment counts and proving times are summed, and per-segment stats are
+    /// concatenated in order. The receipt of `other`, being the last in the computation, is kept;
+    /// the receipt of `self` is dropped.
+    pub fn merge(self, other: ProveInfo) -> ProveInfo {
+        let mut segment_stats = self.segment_stats;
+        segment_stats.extend(other.segment_stats);
+        ProveInfo {
+            receipt: other.receipt,
+            stats: SessionStats {
+                segments: self.stats.segments + other.stats.segments,
+                total_cycles: self.stats.total_cycles + other.stats.total_cycles,
+                user_cycles: self.stats.user_cycles + other.stats.user_cycles,
+            },
+            segment_stats,
+            proving_time: self.proving_time + other.proving_time,
+        }
+    }
+
+    /// Render the stats of this proof in the Prometheus text exposition format.
+    ///
+    /// Each metric is labeled with the given `job_labels`, which are escaped as required by the
+    /// format. The proving time of each segment is reported under an additional `segment` label
+    /// holding its index.
+    pub fn to_prometheus(&self, job_labels: &[(&str, &str)]) -> String {
+        let labels = format_labels(job_labels.iter().copied());
+
+        let metrics = [
+            (
+                "risc0_segments",
+                "Number of segments in the proven session.",
+                self.stats.segments.to_string(),
+            ),
+            (
+                "risc0_total_cycles",
+                "Total cycles, including continuation overhead and po2 padding.",
+                self.stats.total_cycles.to_string(),
+            ),
+            (
+                "risc0_user_cycles",
+                "Cycles spent executing the guest program.",
+                self.stats.user_cycles.to_string(),
+            ),
+            (
+                "risc0_proving_seconds",
+                "Wall-clock time spent proving, including any recursion or compression.",
+                self.proving_time.as_secs_f64().to_string(),
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, help, value) in metrics {
+            writeln!(out, "# HELP {name} {help}").unwrap();
+            writeln!(out, "# TYPE {name} gauge").unwrap();
+            writeln!(out, "{name}{labels} {value}").unwrap();
+        }
+
+        if !self.segment_stats.is_empty() {
+            let name = "risc0_segment_proving_seconds";
+            writeln!(out, "# HELP {name} Wall-clock time spent proving each segment.").unwrap();
+            writeln!(out, "# TYPE {name} gauge").unwrap();
+            for (idx, stats) in self.segment_stats.iter().enumerate() {
+                let idx = idx.to_string();
+                let segment_labels = job_labels.iter().copied().chain([("segment", idx.as_str())]);
+                let labels = format_labels(segment_labels);
+                writeln!(out, "{name}{labels} {}", stats.duration.as_secs_f64()).unwrap();
+            }
+        }
+        out
+    }
+}
+
+fn format_labels<'a>(labels: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let labels = labels
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{labels}}}")
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
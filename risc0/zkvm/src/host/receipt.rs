@@ -21,7 +21,9 @@ pub(crate) mod segment;
 use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
 use core::fmt::Debug;
 
-use anyhow::Result;
+use anyhow::{anyhow, ensure, Result};
+use bytemuck::Pod;
+use hex::FromHex;
 use risc0_core::field::baby_bear::BabyBear;
 use risc0_zkp::{
     core::{
@@ -33,16 +35,20 @@ use risc0_zkp::{
     },
     verify::VerificationError,
 };
+use risc0_zkvm_platform::WORD_SIZE;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
-    serde::{from_slice, Error},
+    serde::{from_slice, to_vec, Deserializer, Error},
     sha::{Digestible, Sha256},
-    Assumptions, MaybePruned, Output, ReceiptClaim,
+    Assumptions, ExitCode, MaybePruned, Output, ReceiptClaim,
 };
 
 pub use self::{compact::CompactReceipt, composite::CompositeReceipt, segment::SegmentReceipt};
-pub use super::recursion::SuccinctReceipt;
+pub use super::recursion::{ClaimTree, SuccinctReceipt};
+use super::recursion::ALLOWED_CONTROL_ROOT;
+#[cfg(feature = "client")]
+use crate::{ProverOpts, ReceiptKind};
 
 /// A receipt attesting to the execution of a guest program.
 ///
@@ -112,14 +118,133 @@ pub struct Receipt {
     /// This data is cryptographically authenticated in
     /// [Receipt::verify].
     pub journal: Journal,
+
+    /// Application-defined tags attached to this receipt, such as a job or tenant ID.
+    ///
+    /// Metadata is not part of the claim, and is not authenticated by verification. It is skipped
+    /// by serde, so that the bincode and [risc0 serializer](crate::serde) encodings of a receipt
+    /// are unchanged; it is carried by [Receipt::to_bytes] and the API protocol.
+    #[serde(skip)]
+    pub metadata: BTreeMap<String, String>,
 }
 
+/// The result of [Receipt::verify_or_dev].
+#[derive(Debug, PartialEq)]
+pub enum VerificationOutcome {
+    /// The receipt was verified with a real proof.
+    Verified,
+
+    /// The receipt is, or depends on, a fake receipt, and was only accepted
+    /// because dev mode is enabled. It proves nothing.
+    DevModeAccepted,
+
+    /// The receipt failed verification.
+    Rejected(VerificationError),
+}
+
+/// Verify that a [SuccinctReceipt] proves a successful execution of the zkVM from the given
+/// `image_id`, committing to a journal with the given digest.
+///
+/// This is the check [Receipt::verify] performs for succinct receipts. Unlike the prover and
+/// executor, receipt verification does not depend on the `client` or `prove` features, so this
+/// is available when building with `default-features = false` for `no_std` + `alloc`
+/// environments such as embedded verifiers.
+pub fn verify_succinct(
+    receipt: &SuccinctReceipt,
+    image_id: Digest,
+    journal_digest: Digest,
+    ctx: &VerifierContext,
+) -> Result<(), VerificationError> {
+    receipt.verify_integrity_with_context(ctx)?;
+    verify_claim(&receipt.claim, image_id, journal_digest)
+}
+
+/// Check that `claim` is for a successful execution from `image_id` with the given journal and no
+/// unresolved assumptions.
+fn verify_claim(
+    claim: &ReceiptClaim,
+    image_id: Digest,
+    journal_digest: Digest,
+) -> Result<(), VerificationError> {
+    // NOTE: Post-state digest and input digest are unconstrained by this method.
+    if claim.pre.digest() != image_id {
+        return Err(VerificationError::ImageVerificationError);
+    }
+
+    // Check the exit code. This verification method requires execution to be
+    // successful.
+    if !claim.exit_code.is_ok() {
+        return Err(VerificationError::UnexpectedExitCode);
+    };
+
+    // Finally check the output hash in the decoded claim against the expected
+    // output.
+    let expected_output = Output {
+        journal: MaybePruned::Pruned(journal_digest),
+        // It is expected that there are no (unresolved) assumptions.
+        assumptions: Assumptions(vec![]).into(),
+    };
+
+    if claim.output.digest() != expected_output.digest() {
+        let empty_output =
+            claim.output.is_none() && journal_digest == Journal::new(Vec::new()).digest();
+        if !empty_output {
+            tracing::debug!(
+                "journal digest: 0x{}, expected output: 0x{}, decoded output: 0x{}",
+                hex::encode(journal_digest),
+                hex::encode(expected_output.digest()),
+                hex::encode(claim.output.digest()),
+            );
+            return Err(VerificationError::JournalDigestMismatch);
+        }
+        tracing::debug!("accepting zero digest for output of receipt with empty journal");
+    }
+
+    Ok(())
+}
+
+/// Magic header at the start of a receipt encoded with [Receipt::to_bytes].
+#[cfg(feature = "client")]
+const RECEIPT_MAGIC: [u8; 4] = *b"R0RC";
+
+/// Version of the [Receipt::to_bytes] encoding, bumped whenever the encoding changes.
+#[cfg(feature = "client")]
+const RECEIPT_FORMAT_VERSION: u8 = 1;
+
 impl Receipt {
     /// Construct a new Receipt
     pub fn new(inner: InnerReceipt, journal: Vec<u8>) -> Self {
         Self {
             inner,
             journal: Journal::new(journal),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Return the metadata value stored under the given key, if any.
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Store a metadata value under the given key, returning the previous value if any.
+    pub fn set_metadata(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.metadata.insert(key.into(), value.into())
+    }
+
+    /// Return true if this receipt has the same seal, claim and journal as `other`.
+    ///
+    /// Unlike a full comparison, this ignores [Receipt::metadata].
+    pub fn seal_eq(&self, other: &Receipt) -> bool {
+        match (
+            to_vec(&(&self.inner, &self.journal)),
+            to_vec(&(&other.inner, &other.journal)),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
         }
     }
 
@@ -149,43 +274,188 @@ impl Receipt {
         image_id: impl Into<Digest>,
     ) -> Result<(), VerificationError> {
         tracing::debug!("Receipt::verify_with_context");
+        self.verify_with_context_and_journal_digest(ctx, image_id.into(), self.journal.digest())
+    }
+
+    /// Verify this receipt like [Receipt::verify], additionally requiring that
+    /// it was proven with the hash function of the given [ProverOpts].
+    ///
+    /// This catches receipts accidentally produced with a different hash suite
+    /// than expected, which fail with [VerificationError::InvalidHashSuite];
+    /// see [VerifierContext::with_allowed_hashfns]. Groth16 receipts do not
+    /// use a hash suite.
+    #[cfg(feature = "client")]
+    pub fn verify_with_prover_opts(
+        &self,
+        image_id: impl Into<Digest>,
+        opts: &ProverOpts,
+    ) -> Result<(), VerificationError> {
+        let ctx = VerifierContext::default().with_allowed_hashfns(&[opts.hashfn.as_str()]);
+        self.verify_with_context(&ctx, image_id)
+    }
+
+    /// Verify that this receipt proves a successful execution of the zkVM from
+    /// the given `image_id`, committing to a journal with the given digest.
+    ///
+    /// This behaves like [Receipt::verify], but checks the proven output
+    /// against `journal_digest` instead of [Receipt::journal]. It allows
+    /// verifying receipts whose journal is kept private and transmitted only as
+    /// a digest; the journal bytes of this receipt are ignored.
+    pub fn verify_with_journal_digest(
+        &self,
+        image_id: impl Into<Digest>,
+        journal_digest: Digest,
+    ) -> Result<(), VerificationError> {
+        self.verify_with_context_and_journal_digest(
+            &VerifierContext::default(),
+            image_id.into(),
+            journal_digest,
+        )
+    }
+
+    fn verify_with_context_and_journal_digest(
+        &self,
+        ctx: &VerifierContext,
+        image_id: Digest,
+        journal_digest: Digest,
+    ) -> Result<(), VerificationError> {
+        if let InnerReceipt::Succinct(inner) = &self.inner {
+            return verify_succinct(inner, image_id, journal_digest, ctx);
+        }
         self.inner.verify_integrity_with_context(ctx)?;
+        verify_claim(&self.inner.claim()?, image_id, journal_digest)
+    }
 
-        // NOTE: Post-state digest and input digest are unconstrained by this method.
-        let claim = self.inner.claim()?;
-        if claim.pre.digest() != image_id.into() {
-            return Err(VerificationError::ImageVerificationError);
+    /// Verify this receipt like [Receipt::verify], reporting whether it was
+    /// only accepted because dev mode is enabled.
+    ///
+    /// Fake receipts produced by the dev mode prover prove nothing, yet
+    /// [Receipt::verify] accepts them whenever `RISC0_DEV_MODE` is set. This
+    /// returns [VerificationOutcome::DevModeAccepted] in that case, and logs a
+    /// warning, so that applications can make the trust boundary visible.
+    pub fn verify_or_dev(&self, image_id: impl Into<Digest>) -> VerificationOutcome {
+        match self.verify(image_id) {
+            Ok(()) if self.inner.contains_fake() => {
+                tracing::warn!("accepted a fake receipt because dev mode is enabled");
+                VerificationOutcome::DevModeAccepted
+            }
+            Ok(()) => VerificationOutcome::Verified,
+            Err(err) => VerificationOutcome::Rejected(err),
         }
+    }
 
-        // Check the exit code. This verification method requires execution to be
-        // successful.
-        if !claim.exit_code.is_ok() {
-            return Err(VerificationError::UnexpectedExitCode);
-        };
+    /// Verify that this receipt proves a successful execution of the given
+    /// guest ELF binary.
+    ///
+    /// This computes the image ID of `elf` and then behaves like
+    /// [Receipt::verify].
+    pub fn verify_against_elf(&self, elf: &[u8]) -> Result<()> {
+        let image_id = risc0_binfmt::compute_image_id(elf)?;
+        Ok(self.verify(image_id)?)
+    }
 
-        // Finally check the output hash in the decoded claim against the expected
-        // output.
-        let expected_output = Output {
-            journal: MaybePruned::Pruned(self.journal.digest()),
-            // It is expected that there are no (unresolved) assumptions.
-            assumptions: Assumptions(vec![]).into(),
-        };
+    /// Verify this receipt, and check that the assumption with the given claim
+    /// digest was resolved in producing it.
+    ///
+    /// Only a [CompositeReceipt] retains the receipts for its resolved
+    /// assumptions; other kinds of receipt return an error, since resolving an
+    /// assumption removes it from the claim.
+    pub fn verify_with_expected_assumption(
+        &self,
+        image_id: impl Into<Digest>,
+        assumption_claim: Digest,
+    ) -> Result<()> {
+        self.verify(image_id).map_err(|err| anyhow!(err))?;
 
-        if claim.output.digest() != expected_output.digest() {
-            let empty_output = claim.output.is_none() && self.journal.bytes.is_empty();
-            if !empty_output {
-                tracing::debug!(
-                    "journal: 0x{}, expected output digest: 0x{}, decoded output digest: 0x{}",
-                    hex::encode(&self.journal.bytes),
-                    hex::encode(expected_output.digest()),
-                    hex::encode(claim.output.digest()),
-                );
-                return Err(VerificationError::JournalDigestMismatch);
+        let composite = self.inner.composite().map_err(|err| anyhow!(err))?;
+        for assumption in composite.assumptions.iter() {
+            if assumption.claim().map_err(|err| anyhow!(err))?.digest() == assumption_claim {
+                return Ok(());
             }
-            tracing::debug!("accepting zero digest for output of receipt with empty journal");
         }
+        Err(anyhow!("assumption {assumption_claim} was not resolved in this receipt"))
+    }
 
-        Ok(())
+    /// The assumptions this receipt is still conditional on, in the order they
+    /// must be resolved.
+    ///
+    /// Each is returned as an [Assumption::Unresolved] holding the assumption's
+    /// claim. Proving that claim and passing the receipt to
+    /// `ProverServer::resolve` removes it. An unconditional receipt has none.
+    /// Returns an error if the receipt's output or assumptions are pruned, as
+    /// the outstanding assumptions cannot then be listed.
+    pub fn unresolved_assumptions(&self) -> Result<Vec<Assumption>> {
+        let claim = self.claim().map_err(|err| anyhow!(err))?;
+        let output = claim
+            .output
+            .as_value()
+            .map_err(|_| anyhow!("receipt claim output is pruned"))?;
+        let Some(output) = output else {
+            return Ok(Vec::new());
+        };
+        let assumptions = output
+            .assumptions
+            .as_value()
+            .map_err(|_| anyhow!("receipt claim assumptions are pruned"))?;
+        Ok(assumptions.0.iter().cloned().map(Assumption::from).collect())
+    }
+
+    /// Verify that this receipt proves an execution of the zkVM from the given
+    /// `image_id` that halted with a guest error, returning the error code.
+    ///
+    /// This is intended for receipts produced with
+    /// `ProverOpts::prove_guest_errors` set. It returns
+    /// [VerificationError::UnexpectedExitCode] if the guest halted
+    /// successfully (`Halted(0)`) or did not halt at all. The journal is not
+    /// checked, since a guest that halts with an error commits no output.
+    pub fn verify_guest_error(
+        &self,
+        image_id: impl Into<Digest>,
+    ) -> Result<u32, VerificationError> {
+        self.inner
+            .verify_integrity_with_context(&VerifierContext::default())?;
+
+        let claim = self.inner.claim()?;
+        if claim.pre.digest() != image_id.into() {
+            return Err(VerificationError::ImageVerificationError);
+        }
+
+        match claim.exit_code {
+            ExitCode::Halted(code) if code != 0 => Ok(code),
+            _ => Err(VerificationError::UnexpectedExitCode),
+        }
+    }
+
+    /// Concatenate the journals of a chain of receipts for paused and resumed
+    /// executions of the same guest.
+    ///
+    /// Each receipt is verified, and each receipt except the last must end in a
+    /// paused state that is the starting state of the next receipt.
+    pub fn concat_journals(receipts: &[&Receipt]) -> Result<Vec<u8>> {
+        let ctx = VerifierContext::default();
+        let mut claims = Vec::with_capacity(receipts.len());
+        for receipt in receipts {
+            receipt
+                .verify_integrity_with_context(&ctx)
+                .map_err(|err| anyhow!(err))?;
+            claims.push(receipt.claim().map_err(|err| anyhow!(err))?);
+        }
+        for (idx, pair) in claims.windows(2).enumerate() {
+            ensure!(
+                matches!(pair[0].exit_code, ExitCode::Paused(_)),
+                "receipt {idx} ends with exit code {:?} instead of a pause",
+                pair[0].exit_code
+            );
+            ensure!(
+                pair[0].post.digest() == pair[1].pre.digest(),
+                "receipt {} does not resume from the post state of receipt {idx}",
+                idx + 1
+            );
+        }
+        Ok(receipts
+            .iter()
+            .flat_map(|receipt| receipt.journal.bytes.iter().copied())
+            .collect())
     }
 
     /// Verify the integrity of this receipt, ensuring the claim and journal
@@ -239,6 +509,122 @@ impl Receipt {
     pub fn claim(&self) -> Result<ReceiptClaim, VerificationError> {
         self.inner.claim()
     }
+
+    /// Decode the journal of this receipt as a value of type `T`, as committed by the guest with
+    /// `env::commit`.
+    ///
+    /// Unlike [Journal::decode], this returns an error if the journal is not a whole number of
+    /// words or if decoding `T` does not consume the whole journal, which usually means the guest
+    /// committed a different type.
+    pub fn journal_decode<T: DeserializeOwned>(&self) -> Result<T> {
+        let bytes = &self.journal.bytes;
+        ensure!(
+            bytes.len() % WORD_SIZE == 0,
+            "journal length {} is not a multiple of the word size",
+            bytes.len()
+        );
+        let words: Vec<u32> = bytemuck::pod_collect_to_vec(bytes);
+        let mut remaining = words.as_slice();
+        let value = T::deserialize(&mut Deserializer::new(&mut remaining))
+            .map_err(|err| anyhow!(err))?;
+        ensure!(
+            remaining.is_empty(),
+            "{} trailing words in the journal after decoding",
+            remaining.len()
+        );
+        Ok(value)
+    }
+
+    /// Return the [ReceiptKind] of this receipt; see [InnerReceipt::kind].
+    #[cfg(feature = "client")]
+    pub fn kind(&self) -> ReceiptKind {
+        self.inner.kind()
+    }
+
+    /// Returns the exit code of each segment of the execution, in order.
+    ///
+    /// Only a [CompositeReceipt] retains the claims of its individual segments,
+    /// so this returns an error for any other kind of receipt. The sequence is
+    /// checked when the receipt is verified; see
+    /// [CompositeReceipt::segment_exit_codes].
+    pub fn segment_exit_codes(&self) -> Result<Vec<ExitCode>, VerificationError> {
+        Ok(self.inner.composite()?.segment_exit_codes())
+    }
+
+    /// The length of the journal in bytes.
+    ///
+    /// The journal is always carried alongside the receipt, so this is the
+    /// length of [Receipt::journal]; the claim only commits to its digest.
+    pub fn journal_len(&self) -> usize {
+        self.journal.bytes.len()
+    }
+
+    /// Decode the journal, whose words are serialized in the given byte order.
+    ///
+    /// See [Journal::decode_with_endian].
+    pub fn decode_journal_with_endian<T: DeserializeOwned>(
+        &self,
+        endian: Endian,
+    ) -> Result<T, Error> {
+        self.journal.decode_with_endian(endian)
+    }
+
+    /// Decode the journal as a single C-compatible plain-old-data value.
+    ///
+    /// See [Journal::decode_cstyle].
+    pub fn decode_journal_cstyle<T: Pod>(&self) -> Result<T> {
+        self.journal.decode_cstyle()
+    }
+
+    /// Decode the journal as a map of keyed values.
+    ///
+    /// See [Journal::decode_map].
+    pub fn journal_map(&self) -> Result<BTreeMap<String, Vec<u8>>> {
+        self.journal.decode_map()
+    }
+
+    /// Encode this receipt as bytes for storage or transport.
+    ///
+    /// The encoding is a 4-byte magic header, a format version byte, and then the receipt and its
+    /// [Receipt::metadata] serialized with bincode, which stores byte vectors such as the journal
+    /// packed. Use [Receipt::from_bytes] to decode it.
+    #[cfg(feature = "client")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = RECEIPT_MAGIC.to_vec();
+        bytes.push(RECEIPT_FORMAT_VERSION);
+        bincode::serialize_into(&mut bytes, &(self, &self.metadata))?;
+        Ok(bytes)
+    }
+
+    /// Decode a receipt encoded with [Receipt::to_bytes].
+    ///
+    /// Returns an error if the header is missing or names a format version this crate does not
+    /// support.
+    #[cfg(feature = "client")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let header_len = RECEIPT_MAGIC.len() + 1;
+        ensure!(
+            bytes.len() >= header_len && bytes[..RECEIPT_MAGIC.len()] == RECEIPT_MAGIC,
+            "not an encoded receipt: missing header"
+        );
+        let version = bytes[RECEIPT_MAGIC.len()];
+        ensure!(
+            version == RECEIPT_FORMAT_VERSION,
+            "unsupported receipt format version {version}, expected {RECEIPT_FORMAT_VERSION}"
+        );
+        let (mut receipt, metadata): (Self, _) = bincode::deserialize(&bytes[header_len..])?;
+        receipt.metadata = metadata;
+        Ok(receipt)
+    }
+}
+
+/// The byte order of the words in a serialized journal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    /// Least significant byte first, as written by the zkVM guest.
+    Little,
+    /// Most significant byte first.
+    Big,
 }
 
 /// A journal is a record of all public commitments for a given proof session.
@@ -258,6 +644,73 @@ impl Journal {
     pub fn decode<T: DeserializeOwned>(&self) -> Result<T, Error> {
         from_slice(&self.bytes)
     }
+
+    /// Decode the journal bytes by using the [risc0 deserializer](crate::serde),
+    /// reading each word of the journal in the given byte order.
+    ///
+    /// Journals committed by the guest are little-endian. This is useful when
+    /// the journal bytes were produced or byte-swapped by a big-endian system.
+    pub fn decode_with_endian<T: DeserializeOwned>(&self, endian: Endian) -> Result<T, Error> {
+        let words: Vec<u32> = self
+            .bytes
+            .chunks(WORD_SIZE)
+            .map(|chunk| {
+                let mut word = [0u8; WORD_SIZE];
+                word[..chunk.len()].copy_from_slice(chunk);
+                match endian {
+                    Endian::Little => u32::from_le_bytes(word),
+                    Endian::Big => u32::from_be_bytes(word),
+                }
+            })
+            .collect();
+        from_slice(&words)
+    }
+
+    /// Decode the journal bytes as a single C-compatible plain-old-data value.
+    ///
+    /// Unlike [Journal::decode], this does not use the
+    /// [risc0 serializer](crate::serde); the journal is read as the raw memory
+    /// layout of `T`, as committed by the guest with
+    /// `env::commit_slice(core::slice::from_ref(&value))`. Using a `#[repr(C)]`
+    /// type gives a layout that consumers in other languages can rely on.
+    pub fn decode_cstyle<T: Pod>(&self) -> Result<T> {
+        ensure!(
+            self.bytes.len() == core::mem::size_of::<T>(),
+            "journal length {} does not match the size of the decoded type ({} bytes)",
+            self.bytes.len(),
+            core::mem::size_of::<T>()
+        );
+        Ok(bytemuck::pod_read_unaligned(&self.bytes))
+    }
+
+    /// Decode the journal bytes as a sequence of keyed values.
+    ///
+    /// The journal must consist entirely of pairs committed by the guest with
+    /// `env::commit_kv`, in strictly increasing key order. Requiring the order
+    /// means each map has exactly one journal encoding, and so one claim digest.
+    pub fn decode_map(&self) -> Result<BTreeMap<String, Vec<u8>>> {
+        ensure!(
+            self.bytes.len() % WORD_SIZE == 0,
+            "journal length {} is not a multiple of the word size",
+            self.bytes.len()
+        );
+        let words: Vec<u32> = bytemuck::pod_collect_to_vec(&self.bytes);
+        let mut reader = words.as_slice();
+        let mut map = BTreeMap::new();
+        while !reader.is_empty() {
+            let (key, value): (String, Vec<u8>) =
+                Deserialize::deserialize(&mut Deserializer::new(&mut reader))
+                    .map_err(|err| anyhow!(err))?;
+            if let Some((last, _)) = map.last_key_value() {
+                ensure!(
+                    &key > last,
+                    "journal key {key:?} is not committed in increasing order after {last:?}"
+                );
+            }
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
 }
 
 impl risc0_binfmt::Digestible for Journal {
@@ -324,6 +777,19 @@ impl InnerReceipt {
         }
     }
 
+    // True if this receipt, or any receipt it depends on, is a fake receipt produced in dev mode.
+    fn contains_fake(&self) -> bool {
+        match self {
+            InnerReceipt::Composite(x) => {
+                x.segments.iter().any(|s| s.seal.is_empty())
+                    || x.assumptions.iter().any(InnerReceipt::contains_fake)
+            }
+            InnerReceipt::Compact(_) => false,
+            InnerReceipt::Succinct(x) => x.seal.is_empty(),
+            InnerReceipt::Fake { .. } => true,
+        }
+    }
+
     /// Returns the [InnerReceipt::Composite] arm.
     pub fn composite(&self) -> Result<&CompositeReceipt, VerificationError> {
         if let InnerReceipt::Composite(x) = self {
@@ -360,6 +826,19 @@ impl InnerReceipt {
             InnerReceipt::Fake { claim } => Ok(claim.clone()),
         }
     }
+
+    /// Return the [ReceiptKind] of this receipt.
+    ///
+    /// A fake receipt stands in for any kind of receipt in dev mode, and is reported as
+    /// [ReceiptKind::Composite], the least compressed kind.
+    #[cfg(feature = "client")]
+    pub fn kind(&self) -> ReceiptKind {
+        match self {
+            InnerReceipt::Composite(_) | InnerReceipt::Fake { .. } => ReceiptKind::Composite,
+            InnerReceipt::Succinct(_) => ReceiptKind::Succinct,
+            InnerReceipt::Compact(_) => ReceiptKind::Groth16,
+        }
+    }
 }
 
 /// An assumption attached to a guest execution as a result of calling
@@ -428,6 +907,50 @@ impl From<ReceiptClaim> for Assumption {
 pub struct VerifierContext {
     /// A registry of hash functions to be used by the verification process.
     pub suites: BTreeMap<String, HashSuite<BabyBear>>,
+
+    /// The recursion control roots that a [SuccinctReceipt] may be proven
+    /// against. Defaults to [ALLOWED_CONTROL_ROOT].
+    pub control_roots: Vec<Digest>,
+
+    /// The minimum proven soundness, in bits, that a [SegmentReceipt] must
+    /// provide to pass verification. Defaults to no minimum.
+    ///
+    /// Computing soundness requires the `std` feature; without it, any
+    /// minimum causes segment receipts to fail verification.
+    pub min_soundness_bits: Option<f32>,
+}
+
+impl VerifierContext {
+    /// Accept succinct receipts proven against any of the given control roots.
+    ///
+    /// This is useful during an upgrade of the recursion circuit, when valid
+    /// receipts may have been produced with either the old or the new set of
+    /// recursion programs.
+    pub fn with_control_roots(mut self, control_roots: Vec<Digest>) -> Self {
+        self.control_roots = control_roots;
+        self
+    }
+
+    /// Only accept receipts proven with one of the given hash functions.
+    ///
+    /// Receipts using any other hash function fail verification with
+    /// [VerificationError::InvalidHashSuite]. Note that a [SuccinctReceipt] is
+    /// always proven with `poseidon2`, so it is rejected unless that is allowed.
+    pub fn with_allowed_hashfns(mut self, hashfns: &[&str]) -> Self {
+        self.suites.retain(|name, _| hashfns.contains(&name.as_str()));
+        self
+    }
+
+    /// Reject segment receipts whose proven soundness is below `bits`.
+    ///
+    /// Soundness is computed with [risc0_zkp::verify::soundness::proven] from
+    /// the circuit taps and the segment po2 recorded on the seal, so receipts
+    /// proven with a po2 too small to reach `bits` fail verification with
+    /// [VerificationError::InsufficientSoundness].
+    pub fn with_min_soundness_bits(mut self, bits: f32) -> Self {
+        self.min_soundness_bits = Some(bits);
+        self
+    }
 }
 
 impl Default for VerifierContext {
@@ -438,6 +961,181 @@ impl Default for VerifierContext {
                 ("poseidon2".into(), Poseidon2HashSuite::new_suite()),
                 ("sha-256".into(), Sha256HashSuite::new_suite()),
             ]),
+            control_roots: vec![Digest::from_hex(ALLOWED_CONTROL_ROOT).unwrap()],
+            min_soundness_bits: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::{Pod, Zeroable};
+
+    use risc0_zkp::verify::VerificationError;
+
+    #[cfg(feature = "client")]
+    use super::RECEIPT_FORMAT_VERSION;
+    use super::{Assumption, Endian, InnerReceipt, Journal, Receipt, VerificationOutcome};
+    use crate::{
+        serde::to_vec,
+        sha::{Digest, Digestible},
+        Assumptions, ExitCode, MaybePruned, Output, ReceiptClaim,
+    };
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct Point {
+        x: u32,
+        y: u32,
+        tag: [u8; 8],
+    }
+
+    // SAFETY: Point is repr(C), has no padding, and every bit pattern is valid.
+    unsafe impl Zeroable for Point {}
+    unsafe impl Pod for Point {}
+
+    #[test]
+    fn decode_with_endian() {
+        let value = (0x01020304u32, 0xaabbccddu32);
+        let words = to_vec(&value).unwrap();
+
+        let little = Journal::new(bytemuck::cast_slice(&words).to_vec());
+        assert_eq!(
+            little.decode_with_endian::<(u32, u32)>(Endian::Little).unwrap(),
+            value
+        );
+        assert_eq!(
+            little.decode_with_endian::<(u32, u32)>(Endian::Big).unwrap(),
+            (0x04030201, 0xddccbbaa)
+        );
+
+        let big = Journal::new(words.iter().flat_map(|x| x.to_be_bytes()).collect());
+        assert_eq!(big.decode_with_endian::<(u32, u32)>(Endian::Big).unwrap(), value);
+    }
+
+    #[test]
+    fn decode_cstyle() {
+        let point = Point {
+            x: 7,
+            y: 0xdeadbeef,
+            tag: *b"cstyle!\0",
+        };
+        let journal = Journal::new(bytemuck::cast_slice(&[point]).to_vec());
+        assert_eq!(journal.decode_cstyle::<Point>().unwrap(), point);
+
+        let truncated = Journal::new(journal.bytes[..12].to_vec());
+        assert!(truncated.decode_cstyle::<Point>().is_err());
+    }
+
+    #[test]
+    fn decode_map() {
+        let pairs = [("alpha", vec![1u8]), ("beta", vec![2, 3]), ("gamma", vec![])];
+        let words: Vec<u32> = pairs.iter().flat_map(|pair| to_vec(pair).unwrap()).collect();
+        let journal = Journal::new(bytemuck::cast_slice(&words).to_vec());
+        let map = journal.decode_map().unwrap();
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["alpha", "beta", "gamma"]);
+        assert_eq!(map["beta"], [2, 3]);
+
+        let words: Vec<u32> = pairs.iter().rev().flat_map(|pair| to_vec(pair).unwrap()).collect();
+        let journal = Journal::new(bytemuck::cast_slice(&words).to_vec());
+        assert!(journal.decode_map().is_err());
+    }
+
+    #[test]
+    fn journal_decode() {
+        let claim = claim_with_output(None);
+        let digest = Digest::from([1, 2, 3, 4, 5, 6, 7, 8]);
+        let words = to_vec(&digest).unwrap();
+        let receipt = Receipt::new(
+            InnerReceipt::Fake {
+                claim: claim.clone(),
+            },
+            bytemuck::cast_slice(&words).to_vec(),
+        );
+        assert_eq!(receipt.journal_decode::<Digest>().unwrap(), digest);
+        assert!(receipt.journal_decode::<u32>().is_err());
+        assert!(receipt.journal_decode::<(Digest, u32)>().is_err());
+
+        let receipt = Receipt::new(InnerReceipt::Fake { claim }, b"abc".to_vec());
+        assert!(receipt.journal_decode::<u32>().is_err());
+    }
+
+    fn claim_with_output(output: Option<Output>) -> ReceiptClaim {
+        ReceiptClaim {
+            pre: MaybePruned::Pruned(Digest::ZERO),
+            post: MaybePruned::Pruned(Digest::ZERO),
+            exit_code: ExitCode::Halted(0),
+            input: Digest::ZERO,
+            output: MaybePruned::Value(output),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "client")]
+    fn receipt_bytes() {
+        let claim = claim_with_output(None);
+        let mut receipt = Receipt::new(InnerReceipt::Fake { claim }, vec![7u8; 1024]);
+        receipt.set_metadata("job_id", "1234");
+
+        let bytes = receipt.to_bytes().unwrap();
+        assert_eq!(&bytes[..4], b"R0RC");
+        assert_eq!(Receipt::from_bytes(&bytes).unwrap(), receipt);
+
+        // The journal is stored one byte per byte rather than one word per byte.
+        assert!(bytes.len() < 2 * receipt.journal.bytes.len(), "{}", bytes.len());
+
+        let mut future = bytes.clone();
+        future[4] = RECEIPT_FORMAT_VERSION + 1;
+        let err = Receipt::from_bytes(&future).unwrap_err().to_string();
+        assert!(err.contains("unsupported receipt format version"), "{err}");
+        assert!(Receipt::from_bytes(&bytes[4..]).is_err());
+    }
+
+    #[test]
+    fn unresolved_assumptions() {
+        let unconditional = Receipt::new(
+            InnerReceipt::Fake {
+                claim: claim_with_output(None),
+            },
+            Vec::new(),
+        );
+        assert!(unconditional.unresolved_assumptions().unwrap().is_empty());
+
+        let assumption = claim_with_output(None);
+        let output = |assumptions: MaybePruned<Assumptions>| Output {
+            journal: MaybePruned::Pruned(Digest::ZERO),
+            assumptions,
+        };
+        let claim = claim_with_output(Some(output(
+            Assumptions(vec![assumption.clone().into()]).into(),
+        )));
+        let conditional = Receipt::new(InnerReceipt::Fake { claim }, Vec::new());
+        let unresolved = conditional.unresolved_assumptions().unwrap();
+        assert_eq!(unresolved.len(), 1);
+        let Assumption::Unresolved(claim) = &unresolved[0] else {
+            panic!("expected an unresolved assumption");
+        };
+        assert_eq!(claim.digest(), assumption.digest());
+
+        let pruned = claim_with_output(Some(output(MaybePruned::Pruned(Digest::ZERO))));
+        let pruned = Receipt::new(InnerReceipt::Fake { claim: pruned }, Vec::new());
+        assert!(pruned.unresolved_assumptions().is_err());
+    }
+
+    #[test]
+    fn verify_or_dev() {
+        let receipt = Receipt::new(
+            InnerReceipt::Fake {
+                claim: claim_with_output(None),
+            },
+            Vec::new(),
+        );
+        // Fake receipts are rejected outside of dev mode.
+        assert!(!crate::is_dev_mode());
+        assert_eq!(
+            receipt.verify_or_dev(Digest::ZERO),
+            VerificationOutcome::Rejected(VerificationError::InvalidProof)
+        );
+        assert!(receipt.inner.contains_fake());
+    }
+}
@@ -56,6 +56,13 @@ impl CompositeReceipt {
         ctx: &VerifierContext,
     ) -> Result<(), VerificationError> {
         tracing::debug!("CompositeReceipt::verify_integrity_with_context");
+        // All segments of a continuation are expected to be proven with the same hash function.
+        if let Some(first) = self.segments.first() {
+            if self.segments.iter().any(|x| x.hashfn != first.hashfn) {
+                return Err(VerificationError::MixedHashFunctions);
+            }
+        }
+
         // Verify the continuation, by verifying every segment receipt in order.
         let (final_receipt, receipts) = self
             .segments
@@ -154,6 +161,18 @@ impl CompositeReceipt {
         })
     }
 
+    /// Returns the exit code of each segment, in execution order.
+    ///
+    /// Verification checks that every segment but the last ended in
+    /// [ExitCode::SystemSplit], so these are only meaningful once the receipt
+    /// has been verified.
+    pub fn segment_exit_codes(&self) -> Vec<ExitCode> {
+        self.segments
+            .iter()
+            .map(|segment| segment.claim.exit_code)
+            .collect()
+    }
+
     /// Check that the output fields in the given receipt claim are
     /// consistent with the exit code, and with the journal_digest and
     /// assumptions encoded on self.
@@ -26,7 +26,7 @@ mod tests;
 
 pub use risc0_circuit_recursion::control_id::ALLOWED_CONTROL_ROOT;
 
-pub use self::receipt::SuccinctReceipt;
+pub use self::receipt::{ClaimTree, SuccinctReceipt};
 #[cfg(feature = "prove")]
 pub use self::{
     prove::{
@@ -35,5 +35,7 @@ pub use self::{
     },
     receipt::valid_control_ids,
 };
+#[cfg(feature = "prove")]
+pub(crate) use self::prove::{join_claim, resolve_claim};
 
 const CIRCUIT: risc0_circuit_recursion::CircuitImpl = risc0_circuit_recursion::CircuitImpl::new();
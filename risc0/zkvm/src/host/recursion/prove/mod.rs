@@ -92,6 +92,7 @@ pub fn lift(segment_receipt: &SegmentReceipt) -> Result<SuccinctReceipt> {
         seal: receipt.seal,
         control_id: receipt.control_id,
         claim: claim_decoded.merge(&segment_receipt.claim)?,
+        claim_tree: None,
     })
 }
 
@@ -109,13 +110,7 @@ pub fn join(a: &SuccinctReceipt, b: &SuccinctReceipt) -> Result<SuccinctReceipt>
     out_stream.extend(receipt.output.iter());
 
     // Construct the expected claim that should have result from the join.
-    let ab_claim = ReceiptClaim {
-        pre: a.claim.pre.clone(),
-        post: b.claim.post.clone(),
-        exit_code: b.claim.exit_code,
-        input: a.claim.input,
-        output: b.claim.output.clone(),
-    };
+    let ab_claim = join_claim(&a.claim, &b.claim);
 
     let claim_decoded = ReceiptClaim::decode(&mut out_stream)?;
     tracing::debug!("Proving join finished: decoded claim = {claim_decoded:#?}");
@@ -123,6 +118,7 @@ pub fn join(a: &SuccinctReceipt, b: &SuccinctReceipt) -> Result<SuccinctReceipt>
         seal: receipt.seal,
         control_id: receipt.control_id,
         claim: claim_decoded.merge(&ab_claim)?,
+        claim_tree: None,
     })
 }
 
@@ -144,21 +140,8 @@ pub fn resolve(
         assumption.claim,
     );
 
-    // Construct the resolved claim by copying the conditional receipt claim and resolving
-    // the head assumption. If this fails, then so would the resolve program.
-    let mut resolved_claim = conditional.claim.clone();
-    resolved_claim
-        .output
-        .as_value_mut()
-        .context("conditional receipt output is pruned")?
-        .as_mut()
-        .ok_or(anyhow!(
-            "conditional receipt has empty output and no assumptions"
-        ))?
-        .assumptions
-        .as_value_mut()
-        .context("conditional receipt assumptions are pruned")?
-        .resolve(&assumption.claim.digest())?;
+    // If resolving the claim fails, then so would the resolve program.
+    let resolved_claim = resolve_claim(&conditional.claim, &assumption.claim)?;
 
     let mut prover = Prover::new_resolve(conditional, assumption, ProverOpts::default())?;
     let receipt = prover.run()?;
@@ -171,9 +154,45 @@ pub fn resolve(
         seal: receipt.seal,
         control_id: receipt.control_id,
         claim: claim_decoded.merge(&resolved_claim)?,
+        claim_tree: None,
     })
 }
 
+/// The claim proven by joining receipts for the consecutive claims `a` and `b`.
+pub(crate) fn join_claim(a: &ReceiptClaim, b: &ReceiptClaim) -> ReceiptClaim {
+    ReceiptClaim {
+        pre: a.pre.clone(),
+        post: b.post.clone(),
+        exit_code: b.exit_code,
+        input: a.input,
+        output: b.output.clone(),
+    }
+}
+
+/// The claim proven by resolving the head assumption of `conditional` with a receipt for
+/// `assumption`.
+///
+/// The resolved claim is a copy of the conditional claim with the head assumption removed.
+pub(crate) fn resolve_claim(
+    conditional: &ReceiptClaim,
+    assumption: &ReceiptClaim,
+) -> Result<ReceiptClaim> {
+    let mut resolved_claim = conditional.clone();
+    resolved_claim
+        .output
+        .as_value_mut()
+        .context("conditional receipt output is pruned")?
+        .as_mut()
+        .ok_or(anyhow!(
+            "conditional receipt has empty output and no assumptions"
+        ))?
+        .assumptions
+        .as_value_mut()
+        .context("conditional receipt assumptions are pruned")?
+        .resolve(&assumption.digest())?;
+    Ok(resolved_claim)
+}
+
 /// Prove the verification of a recursion receipt using the Poseidon254 hash function for FRI.
 ///
 /// The identity_p254 program is used as the last step in the prover pipeline before running the
@@ -191,6 +210,7 @@ pub fn identity_p254(a: &SuccinctReceipt) -> Result<SuccinctReceipt> {
         seal: receipt.seal,
         control_id: receipt.control_id,
         claim,
+        claim_tree: None,
     })
 }
 
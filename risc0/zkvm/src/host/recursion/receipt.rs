@@ -12,14 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloc::{collections::VecDeque, vec::Vec};
+use alloc::{boxed::Box, collections::VecDeque, vec, vec::Vec};
 
 use hex::FromHex;
 use risc0_binfmt::read_sha_halfs;
-use risc0_circuit_recursion::{
-    control_id::{ALLOWED_CONTROL_IDS, ALLOWED_CONTROL_ROOT},
-    CircuitImpl,
-};
+use risc0_circuit_recursion::{control_id::ALLOWED_CONTROL_IDS, CircuitImpl};
 use risc0_core::field::baby_bear::BabyBearElem;
 use risc0_zkp::{adapter::CircuitInfo, core::digest::Digest, verify::VerificationError};
 use serde::{Deserialize, Serialize};
@@ -53,9 +50,94 @@ pub struct SuccinctReceipt {
 
     /// [ReceiptClaim] containing information about the execution that this receipt proves.
     pub claim: ReceiptClaim,
+
+    /// The tree of claims that were folded into this receipt, if it was retained.
+    #[serde(skip)]
+    pub(crate) claim_tree: Option<ClaimTree>,
+}
+
+/// The tree of claims folded together by the recursion programs to produce a [SuccinctReceipt].
+#[derive(Clone, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ClaimTree {
+    /// A claim proven by a single lifted segment, or by a receipt with no retained history.
+    Leaf(ReceiptClaim),
+
+    /// A claim proven by joining two consecutive spans of the same session.
+    Join {
+        /// The joined claim.
+        claim: ReceiptClaim,
+        /// The tree for the earlier span.
+        left: Box<ClaimTree>,
+        /// The tree for the later span.
+        right: Box<ClaimTree>,
+    },
+
+    /// A claim proven by resolving an assumption of a conditional claim.
+    Resolve {
+        /// The resolved claim.
+        claim: ReceiptClaim,
+        /// The tree for the conditional claim.
+        conditional: Box<ClaimTree>,
+        /// The tree for the claim of the assumption.
+        assumption: Box<ClaimTree>,
+    },
+}
+
+impl ClaimTree {
+    /// Take the tree retained by the given receipt, or a leaf for its claim.
+    pub(crate) fn of(receipt: SuccinctReceipt) -> Self {
+        receipt
+            .claim_tree
+            .unwrap_or_else(|| Self::Leaf(receipt.claim))
+    }
+
+    /// The claim at the root of this tree.
+    pub fn claim(&self) -> &ReceiptClaim {
+        match self {
+            Self::Leaf(claim) => claim,
+            Self::Join { claim, .. } | Self::Resolve { claim, .. } => claim,
+        }
+    }
+
+    /// The claims at the leaves of this tree, in order.
+    pub fn leaves(&self) -> Vec<&ReceiptClaim> {
+        match self {
+            Self::Leaf(claim) => vec![claim],
+            Self::Join { left, right, .. } => [left.leaves(), right.leaves()].concat(),
+            Self::Resolve {
+                conditional,
+                assumption,
+                ..
+            } => [conditional.leaves(), assumption.leaves()].concat(),
+        }
+    }
 }
 
 impl SuccinctReceipt {
+    /// Construct a receipt for the given claim with an empty seal, as produced by the dev mode
+    /// prover.
+    ///
+    /// Like [InnerReceipt::Fake](crate::InnerReceipt::Fake), this receipt only passes
+    /// verification when dev mode is enabled.
+    #[cfg(feature = "prove")]
+    pub(crate) fn fake(claim: ReceiptClaim) -> Self {
+        Self {
+            seal: vec![],
+            control_id: Digest::ZERO,
+            claim,
+            claim_tree: None,
+        }
+    }
+
+    /// The tree of claims folded into this receipt.
+    ///
+    /// This is only available if the receipt was compressed with
+    /// [crate::ProverOpts::retain_claim_tree] set.
+    pub fn claim_tree(&self) -> Option<&ClaimTree> {
+        self.claim_tree.as_ref()
+    }
+
     /// Verify the integrity of this receipt, ensuring the claim is attested
     /// to by the seal.
     pub fn verify_integrity(&self) -> Result<(), VerificationError> {
@@ -68,6 +150,15 @@ impl SuccinctReceipt {
         &self,
         ctx: &VerifierContext,
     ) -> Result<(), VerificationError> {
+        // Receipts from the dev mode prover have no seal.
+        if self.seal.is_empty() {
+            #[cfg(feature = "std")]
+            if crate::is_dev_mode() {
+                return Ok(());
+            }
+            return Err(VerificationError::InvalidProof);
+        }
+
         // Assemble the list of control IDs, and therefore circuit variants, we will
         // accept.
         let valid_ids = valid_control_ids();
@@ -111,14 +202,15 @@ impl SuccinctReceipt {
             .collect::<Vec<_>>()
             .try_into()
             .map_err(|_| VerificationError::ReceiptFormatError)?;
-        let allowed_root = Digest::from_hex(ALLOWED_CONTROL_ROOT).unwrap();
-        if control_root != allowed_root {
+        if !ctx.control_roots.contains(&control_root) {
             tracing::debug!(
-                "succinct receipt does not match the expected control root: decoded: {:#?}, expected: {allowed_root:?}",
+                "succinct receipt does not match any allowed control root: decoded: {:#?}, expected one of: {:?}",
                 control_root,
+                ctx.control_roots,
             );
-            return Err(VerificationError::ControlVerificationError {
-                control_id: control_root,
+            return Err(VerificationError::ControlRootMismatch {
+                control_root,
+                candidates: ctx.control_roots.clone(),
             });
         }
 
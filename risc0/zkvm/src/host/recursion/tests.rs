@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use hex::FromHex;
 use risc0_circuit_recursion::CircuitImpl;
 use risc0_zkp::{
     adapter::CircuitInfo,
     core::digest::{Digest, DIGEST_WORDS},
     field::baby_bear::BabyBearElem,
+    verify::VerificationError,
 };
 use risc0_zkvm_methods::{multi_test::MultiTestSpec, MULTI_TEST_ELF, MULTI_TEST_ID};
 use test_log::test;
@@ -175,6 +177,13 @@ fn generate_busy_loop_segments(hashfn: &str) -> (Session, Vec<SegmentReceipt>) {
         hashfn: hashfn.to_string(),
         prove_guest_errors: false,
         receipt_kind: ReceiptKind::Composite,
+        retain_claim_tree: false,
+        wall_clock_timeout: None,
+        stop_after: None,
+        continue_on_segment_error: false,
+        segment_po2: None,
+        max_total_cycles: None,
+        lift_cache: None,
     };
     let prover = get_prover_server(&opts).unwrap();
 
@@ -294,6 +303,30 @@ fn test_recursion_lift_resolve_e2e() {
     succinct_receipt.verify(MULTI_TEST_ID).unwrap();
 }
 
+#[cfg_attr(
+    not(all(feature = "metal", target_os = "macos", target_arch = "x86_64")),
+    test
+)]
+fn verify_control_roots() {
+    let (_, segments) = generate_busy_loop_segments("poseidon2");
+    let receipt = lift(&segments[0]).unwrap();
+
+    let control_root = Digest::from_hex(ALLOWED_CONTROL_ROOT).unwrap();
+    let decoy = Digest::from([1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let ctx = VerifierContext::default().with_control_roots(vec![decoy, control_root]);
+    receipt.verify_integrity_with_context(&ctx).unwrap();
+
+    let ctx = VerifierContext::default().with_control_roots(vec![decoy]);
+    assert_eq!(
+        receipt.verify_integrity_with_context(&ctx).unwrap_err(),
+        VerificationError::ControlRootMismatch {
+            control_root,
+            candidates: vec![decoy],
+        }
+    );
+}
+
 #[test]
 fn stable_root() {
     // This tests that none of the control IDs have changed unexpectedly.
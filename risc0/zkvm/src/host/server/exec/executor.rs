@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{cell::RefCell, io::Write, mem, rc::Rc, sync::Arc, time::Instant};
+use std::{
+    cell::RefCell, collections::HashMap, io::Write, mem, rc::Rc, sync::Arc, time::Instant,
+};
 
-use anyhow::{Context as _, Result};
+use anyhow::{ensure, Context as _, Result};
 use risc0_binfmt::{MemoryImage, Program};
 use risc0_circuit_rv32im::prove::emu::{
     addr::ByteAddr,
@@ -24,17 +26,21 @@ use risc0_circuit_rv32im::prove::emu::{
     },
 };
 use risc0_zkp::core::digest::Digest;
-use risc0_zkvm_platform::{fileno, memory::GUEST_MAX_MEM, PAGE_SIZE};
+use risc0_zkvm_platform::{fileno, memory::GUEST_MAX_MEM, syscall::nr::SYS_EMIT_EVENT, PAGE_SIZE};
 use tempfile::tempdir;
 
+#[cfg(feature = "prove")]
+use crate::{ProveInfo, ProverServer, VerifierContext};
 use crate::{
-    host::client::env::SegmentPath, Assumption, Assumptions, ExecutorEnv, FileSegmentRef, Output,
-    Segment, SegmentRef, Session,
+    host::{client::env::SegmentPath, server::session::null_callback},
+    sha::{Digestible, Impl, Sha256},
+    Assumption, Assumptions, ExecutorEnv, FileSegmentRef, Output, Segment, SegmentRef, Session,
+    TraceCallback, TraceEvent,
 };
 
 use super::{
     profiler::Profiler,
-    syscall::{SyscallContext, SyscallTable},
+    syscall::{SysEmitEvent, SyscallContext, SyscallTable},
 };
 
 // The Executor provides an implementation for the execution phase.
@@ -45,6 +51,8 @@ pub struct ExecutorImpl<'a> {
     image: MemoryImage,
     pub(crate) syscall_table: SyscallTable<'a>,
     profiler: Option<Rc<RefCell<Profiler>>>,
+    pc_stats: Option<Rc<RefCell<PcStats>>>,
+    events: Rc<RefCell<Vec<Vec<u8>>>>,
 }
 
 impl<'a> ExecutorImpl<'a> {
@@ -91,16 +99,28 @@ impl<'a> ExecutorImpl<'a> {
     }
 
     fn with_details(
-        env: ExecutorEnv<'a>,
+        mut env: ExecutorEnv<'a>,
         image: MemoryImage,
         profiler: Option<Rc<RefCell<Profiler>>>,
     ) -> Result<Self> {
-        let syscall_table = SyscallTable::new(&env);
+        let pc_stats = if env.pc_stats {
+            let pc_stats = Rc::new(RefCell::new(PcStats::default()));
+            env.trace.push(pc_stats.clone());
+            Some(pc_stats)
+        } else {
+            None
+        };
+
+        let mut syscall_table = SyscallTable::new(&env);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        syscall_table.with_syscall(SYS_EMIT_EVENT, SysEmitEvent(events.clone()));
         Ok(Self {
             env,
             image,
             syscall_table,
             profiler,
+            pc_stats,
+            events,
         })
     }
 
@@ -115,15 +135,54 @@ impl<'a> ExecutorImpl<'a> {
         self.run_with_callback(|segment| Ok(Box::new(FileSegmentRef::new(&segment, &path)?)))
     }
 
+    /// Count the segments that running this executor produces at the configured segment limit,
+    /// without storing them.
+    ///
+    /// This executes the guest like [ExecutorImpl::run], but drops each [Segment] as soon as it is
+    /// split off rather than writing it to the segment path. Execution consumes the guest input,
+    /// so construct a new [ExecutorImpl] to run the guest for proving.
+    pub fn estimate_segments(&mut self) -> Result<usize> {
+        Ok(self.run_with_callback(null_callback)?.segments.len())
+    }
+
+    /// Run the executor and prove the resulting [Session] with the given
+    /// [ProverServer].
+    ///
+    /// The executor is consumed, and its memory image and environment are
+    /// dropped as soon as execution finishes, so they are not held in memory
+    /// while proving.
+    #[cfg(feature = "prove")]
+    pub fn run_and_prove(
+        mut self,
+        prover: &dyn ProverServer,
+        ctx: &VerifierContext,
+    ) -> Result<ProveInfo> {
+        let mut session = self.run()?;
+        self.fetch_deferred_assumptions(&mut session)?;
+        drop(self);
+        prover.prove_session(ctx, &session)
+    }
+
     /// Run the executor until [crate::ExitCode::Halted] or
     /// [crate::ExitCode::Paused] is reached, producing a [Session] as a result.
     pub fn run_with_callback<F>(&mut self, mut callback: F) -> Result<Session>
     where
         F: FnMut(Segment) -> Result<Box<dyn SegmentRef>>,
     {
+        if let (true, Some(expected)) = (self.env.verify_input_digest, self.env.input_digest) {
+            let actual = *Impl::hash_bytes(&self.env.input);
+            ensure!(
+                actual == expected,
+                "input digest {expected} does not match the digest {actual} of the guest input"
+            );
+        }
+
         nvtx::range_push!("execute");
 
-        let journal = Journal::default();
+        let journal = Journal {
+            buf: Default::default(),
+            callback: self.env.journal_callback.clone(),
+        };
         self.env
             .posix_io
             .borrow_mut()
@@ -141,6 +200,12 @@ impl<'a> ExecutorImpl<'a> {
             self.env.input_digest,
             self.env.trace.clone(),
         );
+        if let Some(addr) = self.env.break_on_write {
+            exec.set_break_on_write(ByteAddr(addr));
+        }
+        if let Some(cycles) = self.env.fixed_segment_cycles {
+            exec.set_fixed_segment_cycles(cycles.try_into()?);
+        }
 
         let start_time = Instant::now();
         let result = exec.run(segment_limit_po2, self.env.session_limit, |inner| {
@@ -211,7 +276,7 @@ impl<'a> ExecutorImpl<'a> {
 
         self.image = result.post_image.clone();
 
-        let session = Session::new(
+        let mut session = Session::new(
             refs,
             self.env.input_digest.unwrap_or_default(),
             session_journal,
@@ -223,6 +288,12 @@ impl<'a> ExecutorImpl<'a> {
             result.pre_state,
             result.post_state,
         );
+        session.breakpoint = result.breakpoint;
+        session.events = self.events.take();
+        session.pc_stats = self
+            .pc_stats
+            .as_ref()
+            .map(|pc_stats| pc_stats.borrow_mut().finalize(session.user_cycles));
 
         tracing::info_span!("executor").in_scope(|| {
             tracing::info!("execution time: {elapsed:?}");
@@ -232,6 +303,26 @@ impl<'a> ExecutorImpl<'a> {
         nvtx::range_pop!();
         Ok(session)
     }
+
+    /// Fetch the receipts for the assumptions added with
+    /// [crate::ExecutorEnvBuilder::add_assumption_by_digest] that the given [Session] used, so
+    /// that proving resolves them.
+    pub(crate) fn fetch_deferred_assumptions(&self, session: &mut Session) -> Result<()> {
+        let assumptions = self.env.assumptions.borrow();
+        for assumption in session.assumptions.iter() {
+            let Assumption::Unresolved(claim) = assumption else {
+                continue;
+            };
+            let digest = claim.digest();
+            if session.deferred_assumptions.contains_key(&digest) {
+                continue;
+            }
+            if let Some(deferred) = assumptions.deferred.get(&digest) {
+                session.deferred_assumptions.insert(digest, deferred.fetch()?);
+            }
+        }
+        Ok(())
+    }
 }
 
 struct ContextAdapter<'a> {
@@ -268,15 +359,50 @@ impl<'a> NewSyscall for ExecutorImpl<'a> {
     }
 }
 
+// Accumulate the user cycles spent at each instruction address.
+//
+// The emulator reports the starting cycle of each instruction, so an instruction is charged
+// once the next one starts, and the last one when execution finishes.
+#[derive(Default)]
+struct PcStats {
+    stats: HashMap<u32, u64>,
+    last: Option<(u32, u64)>,
+}
+
+impl PcStats {
+    fn finalize(&mut self, user_cycles: u64) -> HashMap<u32, u64> {
+        if let Some((pc, cycle)) = self.last.take() {
+            *self.stats.entry(pc).or_default() += user_cycles - cycle;
+        }
+        mem::take(&mut self.stats)
+    }
+}
+
+impl TraceCallback for PcStats {
+    fn trace_callback(&mut self, event: TraceEvent) -> Result<()> {
+        if let TraceEvent::InstructionStart { cycle, pc, .. } = event {
+            if let Some((last_pc, last_cycle)) = self.last.replace((pc, cycle)) {
+                *self.stats.entry(last_pc).or_default() += cycle - last_cycle;
+            }
+        }
+        Ok(())
+    }
+}
+
 // Capture the journal output in a buffer that we can access afterwards.
-#[derive(Clone, Default)]
-struct Journal {
+#[derive(Clone)]
+struct Journal<'a> {
     buf: Rc<RefCell<Vec<u8>>>,
+    callback: Option<Rc<RefCell<dyn FnMut(&[u8]) + 'a>>>,
 }
 
-impl Write for Journal {
+impl Write for Journal<'_> {
     fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
-        self.buf.borrow_mut().write(bytes)
+        let written = self.buf.borrow_mut().write(bytes)?;
+        if let Some(callback) = &self.callback {
+            (callback.borrow_mut())(&bytes[..written]);
+        }
+        Ok(written)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
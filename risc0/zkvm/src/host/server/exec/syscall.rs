@@ -22,8 +22,8 @@ use risc0_zkvm_platform::{
     fileno,
     syscall::{
         nr::{
-            SYS_ARGC, SYS_ARGV, SYS_CYCLE_COUNT, SYS_GETENV, SYS_LOG, SYS_PANIC, SYS_RANDOM,
-            SYS_READ, SYS_VERIFY, SYS_VERIFY_INTEGRITY, SYS_WRITE,
+            SYS_ARGC, SYS_ARGV, SYS_ASSUMPTION_COUNT, SYS_CYCLE_COUNT, SYS_GETENV, SYS_LOG,
+            SYS_PANIC, SYS_RANDOM, SYS_READ, SYS_VERIFY, SYS_VERIFY_INTEGRITY, SYS_WRITE,
         },
         reg_abi::{REG_A3, REG_A4, REG_A5},
         SyscallName, DIGEST_BYTES, DIGEST_WORDS,
@@ -38,7 +38,7 @@ use crate::{
         slice_io::SliceIo,
     },
     sha::{Digest, Digestible},
-    Assumption, MaybePruned, PrunedValueError, ReceiptClaim,
+    MaybePruned, PrunedValueError, ReceiptClaim,
 };
 
 /// A host-side implementation of a system call.
@@ -95,7 +95,8 @@ impl<'a> SyscallTable<'a> {
             .with_syscall(SYS_READ, posix_io.clone())
             .with_syscall(SYS_WRITE, posix_io)
             .with_syscall(SYS_VERIFY, sys_verify.clone())
-            .with_syscall(SYS_VERIFY_INTEGRITY, sys_verify)
+            .with_syscall(SYS_VERIFY_INTEGRITY, sys_verify.clone())
+            .with_syscall(SYS_ASSUMPTION_COUNT, sys_verify)
             .with_syscall(SYS_ARGC, Args(env.args.clone()))
             .with_syscall(SYS_ARGV, Args(env.args.clone()));
         for (syscall, handler) in env.slice_io.borrow().inner.iter() {
@@ -137,6 +138,22 @@ impl Syscall for SysCycleCount {
     }
 }
 
+pub(crate) struct SysEmitEvent(pub Rc<RefCell<Vec<Vec<u8>>>>);
+impl Syscall for SysEmitEvent {
+    fn syscall(
+        &mut self,
+        _syscall: &str,
+        ctx: &mut dyn SyscallContext,
+        _to_guest: &mut [u32],
+    ) -> Result<(u32, u32)> {
+        let buf_ptr = ctx.load_register(REG_A3);
+        let buf_len = ctx.load_register(REG_A4);
+        let event = ctx.load_region(buf_ptr, buf_len)?;
+        self.0.borrow_mut().push(event);
+        Ok((0, 0))
+    }
+}
+
 pub(crate) struct SysGetenv(pub HashMap<String, String>);
 impl Syscall for SysGetenv {
     fn syscall(
@@ -195,12 +212,12 @@ impl Syscall for SysRandom {
 }
 
 #[derive(Clone)]
-pub(crate) struct SysVerify {
-    pub(crate) assumptions: Rc<RefCell<Assumptions>>,
+pub(crate) struct SysVerify<'a> {
+    pub(crate) assumptions: Rc<RefCell<Assumptions<'a>>>,
 }
 
-impl SysVerify {
-    pub(crate) fn new(assumptions: Rc<RefCell<Assumptions>>) -> Self {
+impl<'a> SysVerify<'a> {
+    pub(crate) fn new(assumptions: Rc<RefCell<Assumptions<'a>>>) -> Self {
         Self { assumptions }
     }
 
@@ -212,15 +229,11 @@ impl SysVerify {
         tracing::debug!("SYS_VERIFY_INTEGRITY: {}", hex::encode(claim_digest));
 
         // Iterate over the list looking for a matching assumption.
-        let mut assumption: Option<Assumption> = None;
-        for cached_assumption in self.assumptions.borrow().cached.iter() {
-            if cached_assumption.claim()?.digest() == claim_digest {
-                assumption = Some(cached_assumption.clone());
-                break;
-            }
-        }
+        let assumption = self.assumptions.borrow_mut().find(|assumption| {
+            Ok((assumption.claim()?.digest() == claim_digest).then_some(()))
+        })?;
 
-        let Some(assumption) = assumption else {
+        let Some((assumption, ())) = assumption else {
             return Err(anyhow!(
                 "sys_verify_integrity: failed to resolve claim digest: {claim_digest}"
             ));
@@ -263,35 +276,31 @@ impl SysVerify {
 
         // Iterate over the list looking for a matching assumption. If found, return the
         // post state digest and system exit code.
-        let mut assumption: Option<Assumption> = None;
-        for cached_assumption in self.assumptions.borrow().cached.iter() {
-            let assumption_claim = cached_assumption.claim()?;
+        let assumption = self.assumptions.borrow_mut().find(|assumption| {
+            let assumption_claim = assumption.claim()?;
             let cmp_result = Self::sys_verify_cmp(&assumption_claim, &image_id, &journal_digest);
-            let (post_state_digest, sys_exit_code) = match cmp_result {
-                Ok(None) => continue,
+            match cmp_result {
+                Ok(out) => Ok(out),
                 // If the required values to compare were pruned, go the next assumption.
                 Err(e) => {
                     tracing::debug!(
                         "sys_verify: pruned values in assumption prevented comparison: {e} : {assumption_claim:?}"
                     );
-                    continue;
+                    Ok(None)
                 }
-                Ok(Some(out)) => out,
-            };
-
-            // Write the post_state_digest to the guest buffer as a result.
-            to_guest[..DIGEST_WORDS].copy_from_slice(post_state_digest.as_words());
-            to_guest[DIGEST_WORDS] = sys_exit_code;
-            assumption = Some(cached_assumption.clone());
-            break;
-        }
+            }
+        })?;
 
-        let Some(assumption) = assumption else {
+        let Some((assumption, (post_state_digest, sys_exit_code))) = assumption else {
             return Err(anyhow!(
                 "sys_verify_integrity: failed to resolve journal_digest and image_id: {journal_digest}, {image_id}"
             ));
         };
 
+        // Write the post_state_digest to the guest buffer as a result.
+        to_guest[..DIGEST_WORDS].copy_from_slice(post_state_digest.as_words());
+        to_guest[DIGEST_WORDS] = sys_exit_code;
+
         // Mark the assumption as accessed, pushing it to the head of the list, and return the success code.
         self.assumptions.borrow_mut().accessed.insert(0, assumption);
         Ok((0, 0))
@@ -346,13 +355,19 @@ impl SysVerify {
     }
 }
 
-impl Syscall for SysVerify {
+impl<'a> Syscall for SysVerify<'a> {
     fn syscall(
         &mut self,
         syscall: &str,
         ctx: &mut dyn SyscallContext,
         to_guest: &mut [u32],
     ) -> Result<(u32, u32)> {
+        if syscall == SYS_ASSUMPTION_COUNT.as_str() {
+            let assumptions = self.assumptions.borrow();
+            let count = assumptions.cached.len() + assumptions.pending.len();
+            return Ok((count.try_into()?, 0));
+        }
+
         let from_guest_ptr = ctx.load_register(REG_A3);
         let from_guest_len = ctx.load_register(REG_A4);
         let from_guest: Vec<u8> = ctx.load_region(from_guest_ptr, from_guest_len)?;
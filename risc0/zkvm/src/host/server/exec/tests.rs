@@ -14,7 +14,7 @@
 
 use std::{
     collections::{BTreeMap, HashSet},
-    io::Cursor,
+    io::{Cursor, Read},
     str::from_utf8,
     sync::Mutex,
 };
@@ -22,11 +22,12 @@ use std::{
 use anyhow::Result;
 use bytes::Bytes;
 use risc0_binfmt::{MemoryImage, Program};
+use risc0_circuit_rv32im::prove::emu::testutil;
 use risc0_zkvm_methods::{
-    multi_test::{MultiTestSpec, SYS_MULTI_TEST},
+    multi_test::{MultiTestSpec, TestConfig, SYS_MULTI_TEST},
     BLST_ELF, HELLO_COMMIT_ELF, MULTI_TEST_ELF, RAND_ELF, SLICE_IO_ELF, STANDARD_LIB_ELF,
 };
-use risc0_zkvm_platform::{fileno, syscall::nr::SYS_RANDOM, PAGE_SIZE, WORD_SIZE};
+use risc0_zkvm_platform::{fileno, memory, syscall::nr::SYS_RANDOM, PAGE_SIZE, WORD_SIZE};
 use sha2::{Digest as _, Sha256};
 use test_log::test;
 
@@ -40,7 +41,8 @@ use crate::{
     },
     serde::to_vec,
     sha::{Digest, Digestible},
-    ExecutorEnv, ExecutorImpl, ExitCode,
+    usable_cycles_per_segment, ExecutorEnv, ExecutorImpl, ExitCode, InsnKind, RecursionPlan,
+    SessionManifest,
 };
 
 fn run_test(spec: MultiTestSpec) {
@@ -110,6 +112,28 @@ fn basic() {
     assert_eq!(segment.index, 0);
 }
 
+#[test]
+fn flat_binary() {
+    let words: [u32; 5] = [
+        0x1234b137, // lui x2, 0x1234b000
+        0xf387e1b7, // lui x3, 0xf387e000
+        0x003100b3, // add x1, x2, x3
+        0x000055b7, // lui x11, 0x5
+        0x00000073, // ecall(halt)
+    ];
+    let flat: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+    let image = MemoryImage::from_flat(&flat, 0x4000, 0x4000, PAGE_SIZE as u32).unwrap();
+
+    let session = ExecutorImpl::new(ExecutorEnv::default(), image)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+
+    assert!(MemoryImage::from_flat(&flat, 0x4000, 0x4002, PAGE_SIZE as u32).is_err());
+    assert!(MemoryImage::from_flat(&flat, 0x4000, 0x4014, PAGE_SIZE as u32).is_err());
+}
+
 #[test]
 fn system_split() {
     let entry = 0x4000;
@@ -155,6 +179,147 @@ fn system_split() {
     assert_eq!(segments[1].index, 1);
 }
 
+#[test]
+fn session_report() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    let report = session.report();
+    assert!(report.contains("exit code: Halted(0)"));
+    assert!(report.contains("segments: 1\n"));
+    assert!(report.contains(&format!("total cycles: {}", session.total_cycles)));
+    assert!(report.contains("assumptions: 0"));
+}
+
+#[test]
+fn estimate_segments() {
+    let program = testutil::simple_loop();
+    let executor = || {
+        let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+        let env = ExecutorEnv::builder()
+            .segment_limit_po2(14) // 16k cycles
+            .build()
+            .unwrap();
+        ExecutorImpl::new(env, image).unwrap()
+    };
+    let estimate = executor().estimate_segments().unwrap();
+    let session = executor().run().unwrap();
+    assert_eq!(estimate, 2);
+    assert_eq!(estimate, session.segments.len());
+}
+
+#[test]
+fn pc_stats() {
+    let program = testutil::simple_loop();
+    let run = |profiling: bool| {
+        let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+        let mut builder = ExecutorEnv::builder();
+        builder.segment_limit_po2(14); // 16k cycles
+        if profiling {
+            builder.enable_profiling();
+        }
+        ExecutorImpl::new(builder.build().unwrap(), image)
+            .unwrap()
+            .run()
+            .unwrap()
+    };
+    let plain = run(false);
+    let profiled = run(true);
+    assert!(plain.pc_stats().is_none());
+
+    // Profiling does not change the execution.
+    assert_eq!(profiled.segments.len(), plain.segments.len());
+    assert_eq!(
+        profiled.claim().unwrap().digest(),
+        plain.claim().unwrap().digest()
+    );
+
+    let stats = profiled.pc_stats().unwrap();
+    assert!(!stats.is_empty());
+    assert!(stats.keys().all(|pc| *pc as usize % WORD_SIZE == 0));
+    assert_eq!(stats.values().sum::<u64>(), profiled.user_cycles);
+}
+
+#[test]
+fn recursion_plan() {
+    let session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+
+    let plan = session.recursion_plan();
+    assert_eq!(
+        plan,
+        RecursionPlan {
+            lifts: 2,
+            joins: 1,
+            join_depth: 1,
+            resolves: 0,
+        }
+    );
+    assert_eq!(plan.total_proofs(), 3);
+}
+
+#[test]
+fn usable_cycles() {
+    let segment_limit_po2 = 14; // 16k cycles
+    let usable = usable_cycles_per_segment(segment_limit_po2).unwrap();
+    assert!(usable < 1 << segment_limit_po2);
+    assert_eq!(usable_cycles_per_segment(0), None);
+    assert_eq!(usable_cycles_per_segment(64), None);
+
+    let program = testutil::simple_loop();
+    let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+    let env = ExecutorEnv::builder()
+        .segment_limit_po2(segment_limit_po2)
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::new(env, image).unwrap().run().unwrap();
+    assert_eq!(session.segments.len(), 2);
+
+    // The first segment split because it ran out of usable cycles.
+    let segment = session.segments[0].resolve().unwrap();
+    assert_eq!(segment.inner.exit_code, ExitCode::SystemSplit);
+    assert!(segment.inner.insn_cycles as u64 <= usable);
+}
+
+#[test]
+fn session_manifest() {
+    let session = testutils::run_simple_loop_session();
+
+    let manifest = session.to_manifest().unwrap();
+    assert_eq!(manifest.exit_code, ExitCode::Halted(0));
+    assert_eq!(manifest.segment_count(), 2);
+    assert!(manifest.segment_po2s.iter().all(|&po2| po2 <= 14));
+    assert_eq!(manifest.total_cycles, session.total_cycles);
+
+    let bytes = bincode::serialize(&manifest).unwrap();
+    assert_eq!(bincode::deserialize::<SessionManifest>(&bytes).unwrap(), manifest);
+}
+
+#[test]
+fn instruction_histogram() {
+    let program = testutil::simple_loop();
+    let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+
+    let mut exec = ExecutorImpl::new(ExecutorEnv::default(), image).unwrap();
+    let session = exec.run().unwrap();
+    assert_eq!(session.segments.len(), 1);
+    let segment = session.segments[0].resolve().unwrap();
+
+    let histogram = segment.instruction_histogram().unwrap();
+    assert_eq!(histogram, segment.instruction_histogram().unwrap());
+    assert_eq!(histogram.get(&InsnKind::ADDI), Some(&1002));
+    assert_eq!(histogram.get(&InsnKind::BLT), Some(&1000));
+    assert_eq!(histogram.get(&InsnKind::LUI), Some(&1));
+    assert_eq!(histogram.get(&InsnKind::EANY), Some(&1));
+    assert_eq!(histogram.values().sum::<u64>(), 2004);
+}
+
 #[test]
 fn libm_build() {
     run_test(MultiTestSpec::LibM);
@@ -211,6 +376,329 @@ fn host_syscall_callback_panic() {
     assert_eq!(session.exit_code, ExitCode::Halted(0));
 }
 
+#[test]
+fn fetch_blob() {
+    let blob = vec![7u8; 1000];
+    let digest = blob.digest();
+    let provider_blob = blob.clone();
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::FetchBlob(digest))
+        .unwrap()
+        .blob_provider(move |x| (x == digest).then(|| provider_blob.clone()))
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    let len: u32 = session.journal.unwrap().decode().unwrap();
+    assert_eq!(len as usize, blob.len());
+
+    // A provider returning bytes that do not match the digest fails execution.
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::FetchBlob(digest))
+        .unwrap()
+        .blob_provider(|_| Some(vec![0u8; 1000]))
+        .build()
+        .unwrap();
+    let err = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("does not match"), "{err}");
+}
+
+#[test]
+fn run_id() {
+    let id = *b"0123456789abcdef";
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::RunId)
+        .unwrap()
+        .run_id(id)
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    let committed: [u8; 16] = session.journal.unwrap().decode().unwrap();
+    assert_eq!(committed, id);
+}
+
+#[test]
+fn config() {
+    let config = TestConfig {
+        name: "example".into(),
+        threshold: 42,
+    };
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::Config)
+        .unwrap()
+        .config(&config)
+        .unwrap()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    let threshold: u32 = session.journal.unwrap().decode().unwrap();
+    assert_eq!(threshold, config.threshold);
+}
+
+#[test]
+fn emit_events() {
+    let events = vec![b"first".to_vec(), b"second event".to_vec()];
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::EmitEvents(events.clone()))
+        .unwrap()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    assert_eq!(session.events, events);
+    assert!(session.journal.unwrap().bytes.is_empty());
+}
+
+#[test]
+fn commit_kv() {
+    let pairs = vec![
+        ("apple".to_string(), vec![1]),
+        ("banana".to_string(), vec![2, 2]),
+        ("cherry".to_string(), b"three".to_vec()),
+    ];
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::CommitKv(pairs.clone()))
+        .unwrap()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    let map = session.journal.unwrap().decode_map().unwrap();
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), pairs);
+}
+
+#[test]
+fn commit_kv_unsorted() {
+    let pairs = vec![
+        ("banana".to_string(), vec![2, 2]),
+        ("apple".to_string(), vec![1]),
+    ];
+
+    // Committing keys out of order panics in the guest.
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::CommitKv(pairs.clone()))
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut exec = ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap();
+    let err = exec.run().err().unwrap();
+    assert!(err.to_string().contains("is not greater than the previous key"));
+
+    // Committing them as a map sorts them.
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::CommitMap(pairs))
+        .unwrap()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    let map = session.journal.unwrap().decode_map().unwrap();
+    assert_eq!(map.keys().collect::<Vec<_>>(), ["apple", "banana"]);
+    assert_eq!(map["banana"], [2, 2]);
+}
+
+#[test]
+fn on_journal_write() {
+    let pairs = vec![
+        ("apple".to_string(), vec![1]),
+        ("banana".to_string(), vec![2, 2]),
+    ];
+    let mut chunks = Vec::new();
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::CommitKv(pairs))
+        .unwrap()
+        .on_journal_write(|bytes| chunks.push(bytes.to_vec()))
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks.concat(), session.journal.unwrap().bytes);
+}
+
+#[test]
+fn write_to_channel() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::ReadChannels(vec![10, 11]))
+        .unwrap()
+        .write_to_channel(11, &b"second".to_vec())
+        .unwrap()
+        .write_to_channel(10, &b"first".to_vec())
+        .unwrap()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    let (first, second): (Vec<u8>, Vec<u8>) = session.journal.unwrap().decode().unwrap();
+    assert_eq!(first, b"first");
+    assert_eq!(second, b"second");
+
+    let err = ExecutorEnv::builder()
+        .write_to_channel(fileno::STDIN, &0u32)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("reserved"), "{err}");
+}
+
+#[test]
+fn input_len() {
+    let first = MultiTestSpec::DoNothing;
+    let second = MultiTestSpec::ReadChannels(vec![10, 11]);
+    let mut builder = ExecutorEnv::builder();
+    assert_eq!(builder.input_len(), 0);
+
+    builder.write(&first).unwrap();
+    let offset = builder.input_len();
+    assert_eq!(offset, to_vec(&first).unwrap().len());
+
+    builder.write(&second).unwrap();
+    let offset = offset + to_vec(&second).unwrap().len();
+    assert_eq!(builder.input_len(), offset);
+
+    builder.write_slice(&[1u8, 2, 3]);
+    assert_eq!(builder.input_len(), offset + 1);
+}
+
+#[test]
+fn verify_input_digest() {
+    let input = to_vec(&MultiTestSpec::DoNothing).unwrap();
+    let digest = Digest::try_from(Sha256::digest(bytemuck::cast_slice(&input)).as_slice()).unwrap();
+    let run = |digest: Digest, verify: bool| {
+        let env = ExecutorEnv::builder()
+            .write_slice(&input)
+            .input_digest(digest)
+            .verify_input_digest(verify)
+            .build()
+            .unwrap();
+        ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap().run()
+    };
+
+    assert_eq!(run(digest, true).unwrap().exit_code, ExitCode::Halted(0));
+    assert_eq!(run(Digest::ZERO, false).unwrap().exit_code, ExitCode::Halted(0));
+    let err = run(Digest::ZERO, true).err().unwrap();
+    assert!(err.to_string().contains("does not match"), "{err}");
+}
+
+#[test]
+fn read_chunks() {
+    let data: Vec<u8> = (0..30).collect();
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::ReadChunks {
+            chunk_size: 10,
+            busy_iters: 100_000,
+        })
+        .unwrap()
+        .chunked_input(data.clone())
+        .segment_limit_po2(17)
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    assert!(session.segments.len() > 1);
+
+    // Each chunk is committed exactly once, in order, across the segment splits.
+    assert_eq!(session.journal.unwrap().bytes, data);
+}
+
+#[test]
+fn break_on_write() {
+    // Same mid-memory position as the `memory_io` prover test.
+    const POS: u32 = crate::align_up(
+        (memory::TEXT_START + memory::STACK_TOP) as usize / 2,
+        WORD_SIZE,
+    ) as u32;
+
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::ReadWriteMem {
+            values: vec![(POS, 1), (POS + 4, 2)],
+        })
+        .unwrap()
+        .break_on_write(POS)
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert!(session.breakpoint);
+    assert_eq!(session.exit_code, ExitCode::SessionLimit);
+
+    // The store to the breakpoint is included, but nothing after it.
+    let load_word = |addr: u32| {
+        let mut buf = [0u8; WORD_SIZE];
+        session
+            .post_image
+            .load_region_in_page(addr, &mut buf)
+            .unwrap();
+        u32::from_le_bytes(buf)
+    };
+    assert_eq!(load_word(POS), 1);
+    assert_eq!(load_word(POS + 4), 0);
+}
+
+#[test]
+fn fixed_segment_cycles() {
+    const FIXED_CYCLES: usize = 100_000;
+
+    let segment_cycles = || -> Vec<usize> {
+        let env = ExecutorEnv::builder()
+            .write(&MultiTestSpec::BusyLoop { cycles: 500_000 })
+            .unwrap()
+            .segment_limit_po2(18)
+            .fixed_segment_cycles(FIXED_CYCLES as u64)
+            .build()
+            .unwrap();
+        let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(session.exit_code, ExitCode::Halted(0));
+        session
+            .segments
+            .iter()
+            .map(|x| x.resolve().unwrap().inner.insn_cycles)
+            .collect()
+    };
+
+    let cycles = segment_cycles();
+    assert!(cycles.len() > 4, "{cycles:?}");
+    for cycles in &cycles[..cycles.len() - 1] {
+        assert!(*cycles <= FIXED_CYCLES && *cycles > FIXED_CYCLES - 100, "{cycles}");
+    }
+    assert_eq!(cycles, segment_cycles());
+}
+
 #[test]
 fn sha_accel() {
     run_test(MultiTestSpec::ShaConforms);
@@ -226,6 +714,13 @@ fn rsa_compat() {
     run_test(MultiTestSpec::RsaCompat);
 }
 
+#[test]
+fn bigint_test_cases_seeded() {
+    let cases = testutils::generate_bigint_test_cases_seeded(42, 10);
+    assert_eq!(cases, testutils::generate_bigint_test_cases_seeded(42, 10));
+    assert_ne!(cases, testutils::generate_bigint_test_cases_seeded(43, 10));
+}
+
 #[test]
 fn bigint_accel() {
     let cases = testutils::generate_bigint_test_cases(&mut rand::thread_rng(), 10);
@@ -572,6 +1067,45 @@ mod sys_verify {
             .is_err());
     }
 
+    #[test]
+    fn sys_verify_all() {
+        let hello_commit_session = exec_hello_commit();
+        let spec = &MultiTestSpec::SysVerifyAll(vec![
+            (
+                HELLO_COMMIT_ID.into(),
+                hello_commit_session.journal.clone().unwrap().bytes,
+            ),
+            (MULTI_TEST_ID.into(), Vec::new()),
+            (MULTI_TEST_ID.into(), Vec::new()),
+            // Only verified if the host reports too many assumptions.
+            (HELLO_COMMIT_ID.into(), b"not registered".to_vec()),
+        ]);
+
+        let env = ExecutorEnv::builder()
+            .write(&spec)
+            .unwrap()
+            .add_assumption(hello_commit_session.claim().unwrap())
+            .add_assumption(exec_halt(0).claim().unwrap())
+            .add_assumption(exec_pause(0).claim().unwrap())
+            .build()
+            .unwrap();
+        let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(session.exit_code, ExitCode::Halted(0));
+        assert_eq!(session.journal.unwrap().decode::<u32>().unwrap(), 3);
+        assert_eq!(session.assumptions.len(), 3);
+
+        // With no assumptions registered, nothing is verified.
+        let env = ExecutorEnv::builder().write(&spec).unwrap().build().unwrap();
+        let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(session.journal.unwrap().decode::<u32>().unwrap(), 0);
+    }
+
     #[test]
     fn sys_verify_halt_codes() {
         for code in [0u8, 1, 2, 255] {
@@ -744,6 +1278,25 @@ fn large_sha() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn stdin_stream() {
+    let data: Vec<u8> = (0..3 << 20).map(|i: u32| (i % 251) as u8).collect();
+    let expected = data
+        .iter()
+        .fold(0u32, |checksum, byte| checksum.wrapping_mul(31).wrapping_add(*byte as u32));
+
+    let spec = to_vec(&MultiTestSpec::StdinChecksum).unwrap();
+    let stdin = Cursor::new(bytemuck::cast_slice(&spec)).chain(Cursor::new(&data));
+    let env = ExecutorEnv::builder().stdin(stdin).build().unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    let (len, checksum): (u64, u32) = session.journal.unwrap().decode().unwrap();
+    assert_eq!(len, data.len() as u64);
+    assert_eq!(checksum, expected);
+}
+
 #[test]
 fn std_stdio() {
     const STDIN: &str = "Hello world from stdin!\n";
@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use anyhow::{bail, Result};
 
 use crate::{
     host::{
         prove_info::ProveInfo,
         receipt::{InnerReceipt, SegmentReceipt, SuccinctReceipt},
+        recursion::{join_claim, resolve_claim},
         server::session::null_callback,
     },
     ExecutorEnv, ExecutorImpl, ProverOpts, ProverServer, Receipt, Segment, Session,
@@ -43,7 +46,16 @@ use crate::{
 /// It can be fully disabled at compile time, regardless of environment
 /// variables, by setting the feature flag `disable-dev-mode` on the
 /// `risc0_zkvm` crate.
-pub struct DevModeProver;
+pub struct DevModeProver {
+    opts: ProverOpts,
+}
+
+impl DevModeProver {
+    /// Construct a [DevModeProver] that reports the given [ProverOpts].
+    pub fn new(opts: ProverOpts) -> Self {
+        Self { opts }
+    }
+}
 
 impl ProverServer for DevModeProver {
     fn prove_session(&self, _ctx: &VerifierContext, session: &Session) -> Result<ProveInfo> {
@@ -52,11 +64,7 @@ impl ProverServer for DevModeProver {
             Receipts generated from this process are invalid and should never be used in production."
         );
 
-        if cfg!(feature = "disable-dev-mode") {
-            bail!(
-                "zkVM: dev mode is disabled. Unset RISC0_DEV_MODE environment variable to produce valid proofs"
-            )
-        }
+        ensure_dev_mode_enabled()?;
 
         let claim = session.claim()?;
         let receipt = Receipt::new(
@@ -67,6 +75,8 @@ impl ProverServer for DevModeProver {
         Ok(ProveInfo {
             receipt,
             stats: session.stats(),
+            segment_stats: Vec::new(),
+            proving_time: Duration::ZERO,
         })
     }
 
@@ -78,40 +88,82 @@ impl ProverServer for DevModeProver {
         elf: &[u8],
     ) -> Result<ProveInfo> {
         let mut exec = ExecutorImpl::from_elf(env, elf)?;
-        let session = exec.run_with_callback(null_callback)?;
+        let mut session = exec.run_with_callback(null_callback)?;
+        exec.fetch_deferred_assumptions(&mut session)?;
         self.prove_session(ctx, &session)
     }
 
-    fn prove_segment(&self, _ctx: &VerifierContext, _segment: &Segment) -> Result<SegmentReceipt> {
-        unimplemented!("This is unsupported for dev mode.")
+    fn prove_session_opts(
+        &self,
+        ctx: &VerifierContext,
+        session: &Session,
+        opts: &ProverOpts,
+    ) -> Result<ProveInfo> {
+        DevModeProver::new(opts.clone()).prove_session(ctx, session)
     }
 
-    fn lift(&self, _receipt: &SegmentReceipt) -> Result<SuccinctReceipt> {
-        unimplemented!("This is unsupported for dev mode.")
+    /// Produce a fake [SegmentReceipt] with an empty seal, carrying the claim of the given
+    /// [Segment].
+    fn prove_segment(&self, _ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt> {
+        ensure_dev_mode_enabled()?;
+
+        Ok(SegmentReceipt::fake(
+            segment.index,
+            &self.opts.hashfn,
+            segment.claim(),
+        ))
     }
 
-    fn join(&self, _a: &SuccinctReceipt, _b: &SuccinctReceipt) -> Result<SuccinctReceipt> {
-        unimplemented!("This is unsupported for dev mode.")
+    fn lift(&self, receipt: &SegmentReceipt) -> Result<SuccinctReceipt> {
+        ensure_dev_mode_enabled()?;
+        Ok(SuccinctReceipt::fake(receipt.claim.clone()))
+    }
+
+    fn join(&self, a: &SuccinctReceipt, b: &SuccinctReceipt) -> Result<SuccinctReceipt> {
+        ensure_dev_mode_enabled()?;
+        Ok(SuccinctReceipt::fake(join_claim(&a.claim, &b.claim)))
     }
 
     fn resolve(
         &self,
-        _conditional: &SuccinctReceipt,
-        _assumption: &SuccinctReceipt,
+        conditional: &SuccinctReceipt,
+        assumption: &SuccinctReceipt,
     ) -> Result<SuccinctReceipt> {
-        unimplemented!("This is unsupported for dev mode.")
+        ensure_dev_mode_enabled()?;
+        Ok(SuccinctReceipt::fake(resolve_claim(
+            &conditional.claim,
+            &assumption.claim,
+        )?))
     }
 
-    fn identity_p254(&self, _a: &SuccinctReceipt) -> Result<SuccinctReceipt> {
-        unimplemented!("This is unsupported for dev mode.")
+    fn identity_p254(&self, a: &SuccinctReceipt) -> Result<SuccinctReceipt> {
+        ensure_dev_mode_enabled()?;
+        Ok(SuccinctReceipt::fake(a.claim.clone()))
+    }
+
+    fn effective_opts(&self) -> ProverOpts {
+        self.opts.clone()
     }
 
     fn compress(&self, _opts: &ProverOpts, receipt: &Receipt) -> Result<Receipt> {
-        Ok(Receipt::new(
-            InnerReceipt::Fake {
+        ensure_dev_mode_enabled()?;
+        Ok(Receipt {
+            inner: InnerReceipt::Fake {
                 claim: receipt.claim()?,
             },
-            receipt.journal.bytes.clone(),
-        ))
+            journal: receipt.journal.clone(),
+            metadata: receipt.metadata.clone(),
+        })
+    }
+}
+
+/// Return an error if dev mode was disabled at compile time with the `disable-dev-mode` feature,
+/// so that no fake receipt can be produced.
+fn ensure_dev_mode_enabled() -> Result<()> {
+    if cfg!(feature = "disable-dev-mode") {
+        bail!(
+            "zkVM: dev mode is disabled. Unset RISC0_DEV_MODE environment variable to produce valid proofs"
+        )
     }
+    Ok(())
 }
@@ -16,24 +16,35 @@
 
 mod dev_mode;
 mod prover_impl;
+mod repro;
 #[cfg(test)]
 mod tests;
+mod uploading;
 
-use std::rc::Rc;
+use std::{collections::HashMap, ops::Range, rc::Rc, sync::mpsc::Sender};
 
 use anyhow::{anyhow, bail, ensure, Result};
 use cfg_if::cfg_if;
 use risc0_core::field::baby_bear::{BabyBear, Elem, ExtElem};
-use risc0_zkp::hal::{CircuitHal, Hal};
+use risc0_zkp::{
+    core::digest::Digest,
+    hal::{CircuitHal, Hal},
+};
+use serde::{Deserialize, Serialize};
 
+pub use self::{
+    repro::ReproBundle,
+    uploading::{RemoteClient, RemoteStatus, UploadingProver},
+};
 use self::{dev_mode::DevModeProver, prover_impl::ProverImpl};
 use crate::{
     host::{
         prove_info::ProveInfo,
-        receipt::{CompositeReceipt, InnerReceipt, SegmentReceipt, SuccinctReceipt},
+        receipt::{ClaimTree, CompositeReceipt, InnerReceipt, SegmentReceipt, SuccinctReceipt},
     },
-    is_dev_mode, stark_to_snark, CompactReceipt, ExecutorEnv, ExecutorImpl, ProverOpts, Receipt,
-    ReceiptKind, Segment, Session, VerifierContext,
+    is_dev_mode, sha::Digestible, stark_to_snark, Assumption, Assumptions, CompactReceipt,
+    ExecutorEnv, ExecutorImpl, LiftCache, MaybePruned, ProverOpts, Receipt, ReceiptKind, Segment,
+    Session, VerifierContext,
 };
 
 /// A ProverServer can execute a given ELF binary and produce a [ProveInfo] which contains a [crate::Receipt]
@@ -52,16 +63,41 @@ pub trait ProverServer {
         elf: &[u8],
     ) -> Result<ProveInfo> {
         let mut exec = ExecutorImpl::from_elf(env, elf)?;
-        let session = exec.run()?;
+        let mut session = exec.run()?;
+        exec.fetch_deferred_assumptions(&mut session)?;
         self.prove_session(ctx, &session)
     }
 
     /// Prove the specified [Session].
     fn prove_session(&self, ctx: &VerifierContext, session: &Session) -> Result<ProveInfo>;
 
+    /// Prove the specified [Session] with the given [ProverOpts] in place of the ones this server
+    /// was constructed with.
+    ///
+    /// This avoids constructing a new server to change an option for a single call. The hash
+    /// function is fixed when the server is constructed, so `opts.hashfn` must match it. Servers
+    /// that cannot apply per-call options return an error.
+    fn prove_session_opts(
+        &self,
+        _ctx: &VerifierContext,
+        _session: &Session,
+        _opts: &ProverOpts,
+    ) -> Result<ProveInfo> {
+        bail!("this prover does not support per-call ProverOpts")
+    }
+
     /// Prove the specified [Segment].
     fn prove_segment(&self, ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt>;
 
+    /// Prove a [Segment] serialized with [Segment::to_witness_bytes].
+    ///
+    /// This allows a session to be executed in one process and its segments proven in another.
+    /// The receipt proves the same claim as proving the [Segment] directly with
+    /// [ProverServer::prove_segment].
+    fn prove_witness_bytes(&self, ctx: &VerifierContext, bytes: &[u8]) -> Result<SegmentReceipt> {
+        self.prove_segment(ctx, &Segment::from_witness_bytes(bytes)?)
+    }
+
     /// Lift a [SegmentReceipt] into a [SuccinctReceipt]
     fn lift(&self, receipt: &SegmentReceipt) -> Result<SuccinctReceipt>;
 
@@ -79,6 +115,103 @@ pub trait ProverServer {
     /// Convert a [SuccinctReceipt] with a Poseidon hash function that uses a 254-bit field
     fn identity_p254(&self, a: &SuccinctReceipt) -> Result<SuccinctReceipt>;
 
+    /// The [ProverOpts] this server was constructed with, as it will apply them.
+    ///
+    /// Servers that are not configured through [ProverOpts], such as an [UploadingProver] whose
+    /// remote service chooses its own options, return [ProverOpts::default].
+    fn effective_opts(&self) -> ProverOpts {
+        ProverOpts::default()
+    }
+
+    /// Prove and lift every segment of the specified [Session], without joining them.
+    ///
+    /// The returned receipts are in segment order, and each can be verified on its own. This is
+    /// useful for inspecting the recursion process; the receipts can then be combined with
+    /// [ProverServer::join].
+    fn lift_all(&self, ctx: &VerifierContext, session: &Session) -> Result<Vec<SuccinctReceipt>> {
+        session
+            .segments
+            .iter()
+            .map(|segment_ref| {
+                let segment = segment_ref.resolve()?;
+                let receipt = self.prove_segment(ctx, &segment)?;
+                self.lift(&receipt)
+            })
+            .collect()
+    }
+
+    /// Prove the segments of the specified [Session] whose indices fall in `range`.
+    ///
+    /// The returned receipts are in segment order. This allows the segments of a session to be
+    /// proven on several nodes, each proving its own range, after which the receipts can be
+    /// concatenated into the segments of a [CompositeReceipt].
+    fn prove_segments(
+        &self,
+        ctx: &VerifierContext,
+        session: &Session,
+        range: Range<usize>,
+    ) -> Result<Vec<SegmentReceipt>> {
+        let segment_refs = session.segments.get(range.clone()).ok_or_else(|| {
+            anyhow!(
+                "segment range {range:?} is out of bounds for a session with {} segments",
+                session.segments.len()
+            )
+        })?;
+        segment_refs
+            .iter()
+            .map(|segment_ref| self.prove_segment(ctx, &segment_ref.resolve()?))
+            .collect()
+    }
+
+    /// Lazily prove the segments of the specified [Session], yielding each [SegmentReceipt] as
+    /// soon as it is proven.
+    ///
+    /// Each segment is only proven when the iterator is advanced, and the session hooks fire
+    /// around it as they do in [ProverServer::prove_session]. Receipts are yielded in segment
+    /// order; collecting them gives the segments of a [CompositeReceipt] equivalent to the one
+    /// produced by [ProverServer::prove_session].
+    fn prove_session_streaming<'a>(
+        &'a self,
+        ctx: &'a VerifierContext,
+        session: &'a Session,
+    ) -> Box<dyn Iterator<Item = Result<SegmentReceipt>> + 'a> {
+        Box::new(session.segments.iter().map(move |segment_ref| {
+            let segment = segment_ref.resolve()?;
+            if cancelled(session, &segment) {
+                return Err(ProveError::Cancelled {
+                    segments_completed: Vec::new(),
+                }
+                .into());
+            }
+            for hook in &session.hooks {
+                hook.on_pre_prove_segment(&segment);
+            }
+            let receipt = self.prove_segment(ctx, &segment)?;
+            for hook in &session.hooks {
+                hook.on_post_prove_segment(&segment);
+            }
+            Ok(receipt)
+        }))
+    }
+
+    /// Prove every segment of the specified [Session], sending each [SegmentReceipt] to `tx` as
+    /// soon as it is proven.
+    ///
+    /// This is [ProverServer::prove_session_streaming] driven to completion, so a consumer on
+    /// another thread can verify the receipts incrementally. Returns an error if the receiver has
+    /// been dropped.
+    fn prove_session_channel(
+        &self,
+        ctx: &VerifierContext,
+        session: &Session,
+        tx: Sender<SegmentReceipt>,
+    ) -> Result<()> {
+        for receipt in self.prove_session_streaming(ctx, session) {
+            tx.send(receipt?).map_err(|_| anyhow!("segment receipt channel was closed"))?;
+        }
+        Ok(())
+    }
+
     /// Compress a [CompositeReceipt] into a single [SuccinctReceipt].
     ///
     /// A [CompositeReceipt] may contain an arbitrary number of receipts assembled into
@@ -87,40 +220,76 @@ pub trait ProverServer {
     /// [CompositeReceipt] into a single [SuccinctReceipt] that proves the same top-level claim. It
     /// accomplishes this by iterative application of the recursion programs including lift, join,
     /// and resolve.
+    ///
+    /// If [ProverOpts::retain_claim_tree] of [ProverServer::effective_opts] is set, the returned
+    /// receipt records the tree of claims that were folded together; see
+    /// [SuccinctReceipt::claim_tree]. Lifts are shared through its [ProverOpts::lift_cache], if
+    /// set.
     fn compsite_to_succinct(&self, receipt: &CompositeReceipt) -> Result<SuccinctReceipt> {
-        // Compress all receipts in the top-level session into one succinct receipt for the session.
-        let continuation_receipt = receipt
-            .segments
-            .iter()
-            .try_fold(
-                None,
-                |left: Option<SuccinctReceipt>, right: &SegmentReceipt| -> Result<_> {
-                    Ok(Some(match left {
-                        Some(left) => self.join(&left, &self.lift(right)?)?,
-                        None => self.lift(right)?,
-                    }))
+        let opts = self.effective_opts();
+        composite_to_succinct(
+            self,
+            receipt,
+            opts.lift_cache.as_deref(),
+            opts.retain_claim_tree,
+        )
+    }
+
+    /// Compress a [CompositeReceipt] into a single [SuccinctReceipt], checkpointing progress in
+    /// the given [CompressState].
+    ///
+    /// This performs the same lift, join and resolve steps as
+    /// [ProverServer::compsite_to_succinct], but records each intermediate receipt in `state` as
+    /// soon as it is produced. If a step fails, calling this again with the same receipt and state
+    /// resumes from the last successful step instead of starting over. A state that was used for a
+    /// different receipt is discarded.
+    fn composite_to_succinct_resumable(
+        &self,
+        receipt: &CompositeReceipt,
+        state: &mut CompressState,
+    ) -> Result<SuccinctReceipt> {
+        let opts = self.effective_opts();
+        composite_to_succinct_resumable(
+            self,
+            receipt,
+            state,
+            opts.lift_cache.as_deref(),
+            opts.retain_claim_tree,
+        )
+    }
+
+    /// Resolve every assumption of a conditional [SuccinctReceipt], producing an unconditional
+    /// receipt.
+    ///
+    /// Assumptions are resolved in order by calling [ProverServer::resolve] with the receipt
+    /// found in `assumptions` under the assumption's claim digest; the resolve program verifies
+    /// each of those receipts. Returns an error naming the digest of the first assumption that has
+    /// no receipt in `assumptions`.
+    fn verify_and_resolve(
+        &self,
+        conditional: &SuccinctReceipt,
+        assumptions: &HashMap<Digest, SuccinctReceipt>,
+    ) -> Result<SuccinctReceipt> {
+        let mut receipt = conditional.clone();
+        loop {
+            let Some(output) = receipt.claim.output.as_value()? else {
+                return Ok(receipt);
+            };
+            let head = match &output.assumptions {
+                MaybePruned::Value(list) => match list.first() {
+                    Some(head) => head.digest(),
+                    None => return Ok(receipt),
                 },
-            )?
-            .ok_or(anyhow!(
-                "malformed composite receipt has no continuation segment receipts"
-            ))?;
-
-        // Compress assumptions and resolve them to get the final succinct receipt.
-        receipt.assumptions.iter().try_fold(
-            continuation_receipt,
-            |conditional: SuccinctReceipt, assumption: &InnerReceipt| match assumption {
-                InnerReceipt::Succinct(assumption) => self.resolve(&conditional, assumption),
-                InnerReceipt::Composite(assumption) => {
-                    self.resolve(&conditional, &self.compsite_to_succinct(assumption)?)
+                MaybePruned::Pruned(digest) if *digest == Assumptions::default().digest() => {
+                    return Ok(receipt)
                 }
-                InnerReceipt::Fake { .. } => bail!(
-                    "compressing composite receipts with fake receipt assumptions is not supported"
-                ),
-                InnerReceipt::Compact(_) => bail!(
-                    "compressing composite receipts with Compact receipt assumptions is not supported"
-                )
-            },
-        )
+                MaybePruned::Pruned(_) => bail!("conditional receipt assumptions are pruned"),
+            };
+            let assumption = assumptions
+                .get(&head)
+                .ok_or_else(|| anyhow!("missing receipt for assumption {head}"))?;
+            receipt = self.resolve(&receipt, assumption)?;
+        }
     }
 
     /// Compress a [SuccinctReceipt] into a [CompactReceipt].
@@ -139,39 +308,49 @@ pub trait ProverServer {
     ///
     /// The requested target representation is determined by the [ReceiptKind] specified on the
     /// provided [ProverOpts]. If the receipt is already at least as compressed as the requested
-    /// kind, this is a no-op.
+    /// kind, this is a no-op. Lifts are shared through [ProverOpts::lift_cache], if set.
     fn compress(&self, opts: &ProverOpts, receipt: &Receipt) -> Result<Receipt> {
+        let receipt_kind = target_receipt_kind(opts);
         match &receipt.inner {
-            InnerReceipt::Composite(inner) => match opts.receipt_kind {
+            InnerReceipt::Composite(inner) => match receipt_kind {
                 ReceiptKind::Composite => Ok(receipt.clone()),
                 ReceiptKind::Succinct => {
-                    let succinct_receipt = self.compsite_to_succinct(inner)?;
-                    Ok(Receipt::new(
-                        InnerReceipt::Succinct(succinct_receipt),
-                        receipt.journal.bytes.clone(),
-                    ))
+                    let succinct_receipt = composite_to_succinct(
+                        self,
+                        inner,
+                        opts.lift_cache.as_deref(),
+                        opts.retain_claim_tree,
+                    )?;
+                    Ok(Receipt {
+                        inner: InnerReceipt::Succinct(succinct_receipt),
+                        journal: receipt.journal.clone(),
+                        metadata: receipt.metadata.clone(),
+                    })
                 }
-                ReceiptKind::Compact => {
-                    let succinct_receipt = self.compsite_to_succinct(inner)?;
+                ReceiptKind::Groth16 => {
+                    let succinct_receipt =
+                        composite_to_succinct(self, inner, opts.lift_cache.as_deref(), false)?;
                     let compact_receipt = self.succinct_to_compact(&succinct_receipt)?;
-                    Ok(Receipt::new(
-                        InnerReceipt::Compact(compact_receipt),
-                        receipt.journal.bytes.clone(),
-                    ))
+                    Ok(Receipt {
+                        inner: InnerReceipt::Compact(compact_receipt),
+                        journal: receipt.journal.clone(),
+                        metadata: receipt.metadata.clone(),
+                    })
                 }
             },
-            InnerReceipt::Succinct(inner) => match opts.receipt_kind {
+            InnerReceipt::Succinct(inner) => match receipt_kind {
                 ReceiptKind::Composite | ReceiptKind::Succinct => Ok(receipt.clone()),
-                ReceiptKind::Compact => {
+                ReceiptKind::Groth16 => {
                     let compact_receipt = self.succinct_to_compact(inner)?;
-                    Ok(Receipt::new(
-                        InnerReceipt::Compact(compact_receipt),
-                        receipt.journal.bytes.clone(),
-                    ))
+                    Ok(Receipt {
+                        inner: InnerReceipt::Compact(compact_receipt),
+                        journal: receipt.journal.clone(),
+                        metadata: receipt.metadata.clone(),
+                    })
                 }
             },
-            InnerReceipt::Compact(_) => match opts.receipt_kind {
-                ReceiptKind::Composite | ReceiptKind::Succinct | ReceiptKind::Compact => {
+            InnerReceipt::Compact(_) => match receipt_kind {
+                ReceiptKind::Composite | ReceiptKind::Succinct | ReceiptKind::Groth16 => {
                     Ok(receipt.clone())
                 }
             },
@@ -207,8 +386,277 @@ impl Session {
         let prover = get_prover_server(&ProverOpts::default())?;
         prover.prove_session(&VerifierContext::default(), self)
     }
+
+    /// Return the [Receipt] proving the given assumption of this [Session], including those
+    /// added with [crate::ExecutorEnvBuilder::add_assumption_by_digest] and fetched after
+    /// execution.
+    pub(crate) fn assumption_receipt(&self, assumption: &Assumption) -> Result<Receipt> {
+        if let Assumption::Unresolved(claim) = assumption {
+            if let Some(receipt) = self.deferred_assumptions.get(&claim.digest()) {
+                return Ok(receipt.clone());
+            }
+        }
+        Ok(assumption.as_receipt()?.clone())
+    }
+}
+
+impl Receipt {
+    /// Convert this receipt into a receipt of the given [ReceiptKind] using the given prover.
+    ///
+    /// Unlike [ProverServer::compress], which is a no-op when the receipt is already at least as
+    /// compressed as requested, this returns an error when the requested kind is less compressed
+    /// than the current one (e.g. succinct to composite), since receipts cannot be decompressed.
+    /// Fake receipts are passed through to the prover, which requires dev mode.
+    pub fn convert_to(&self, kind: ReceiptKind, prover: &dyn ProverServer) -> Result<Receipt> {
+        let current = self.kind();
+        ensure!(
+            compression_level(kind) >= compression_level(current),
+            "cannot convert a {current:?} receipt into a less compressed {kind:?} receipt"
+        );
+        prover.compress(&ProverOpts::default().with_receipt_kind(kind), self)
+    }
+}
+
+/// Progress of a resumable compression; see
+/// [ProverServer::composite_to_succinct_resumable].
+///
+/// A default (empty) state starts compression from the beginning. The state can be serialized so
+/// that it survives restarts of the prover.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CompressState {
+    /// Digest of the claim of the receipt being compressed.
+    claim_digest: Option<Digest>,
+    /// Number of segment receipts folded into `compressed`.
+    segments_done: usize,
+    /// Lifted receipt for the next segment, if it has not yet been joined.
+    lifted: Option<SuccinctReceipt>,
+    /// Number of assumptions resolved into `compressed`.
+    assumptions_done: usize,
+    /// Receipt for all of the segments and assumptions processed so far.
+    compressed: Option<SuccinctReceipt>,
+}
+
+impl CompressState {
+    /// Number of segment receipts that have been lifted and joined.
+    pub fn segments_done(&self) -> usize {
+        self.segments_done
+    }
+
+    /// Number of assumptions that have been resolved.
+    pub fn assumptions_done(&self) -> usize {
+        self.assumptions_done
+    }
+}
+
+/// Compress a [CompositeReceipt] as in [ProverServer::compsite_to_succinct], reusing lifts from
+/// the given cache if any.
+fn composite_to_succinct<P: ProverServer + ?Sized>(
+    prover: &P,
+    receipt: &CompositeReceipt,
+    lift_cache: Option<&LiftCache>,
+    retain_claim_tree: bool,
+) -> Result<SuccinctReceipt> {
+    composite_to_succinct_resumable(
+        prover,
+        receipt,
+        &mut CompressState::default(),
+        lift_cache,
+        retain_claim_tree,
+    )
 }
 
+/// Compress a [CompositeReceipt] as in [ProverServer::composite_to_succinct_resumable], reusing
+/// lifts from the given cache if any.
+///
+/// The [ClaimTree] is only built when `retain_claim_tree` is set. Each step moves the trees of
+/// its inputs into its own, so building it is linear in the number of steps.
+fn composite_to_succinct_resumable<P: ProverServer + ?Sized>(
+    prover: &P,
+    receipt: &CompositeReceipt,
+    state: &mut CompressState,
+    lift_cache: Option<&LiftCache>,
+    retain_claim_tree: bool,
+) -> Result<SuccinctReceipt> {
+    let claim_digest = receipt.claim()?.digest();
+    if state.claim_digest != Some(claim_digest) {
+        *state = CompressState {
+            claim_digest: Some(claim_digest),
+            ..Default::default()
+        };
+    }
+    ensure!(
+        !receipt.segments.is_empty(),
+        "malformed composite receipt has no continuation segment receipts"
+    );
+
+    // Lift and join the segment receipts, saving each result before moving on.
+    for segment in &receipt.segments[state.segments_done..] {
+        let lifted = match &state.lifted {
+            Some(lifted) => lifted.clone(),
+            None => {
+                let lifted = lift_cached(prover, lift_cache, segment)?;
+                state.lifted = Some(lifted.clone());
+                lifted
+            }
+        };
+        let joined = match &state.compressed {
+            Some(left) => {
+                let mut joined = prover.join(left, &lifted)?;
+                if retain_claim_tree {
+                    joined.claim_tree = Some(ClaimTree::Join {
+                        claim: joined.claim.clone(),
+                        left: Box::new(ClaimTree::of(state.compressed.take().unwrap())),
+                        right: Box::new(ClaimTree::of(lifted)),
+                    });
+                }
+                joined
+            }
+            None => lifted,
+        };
+        state.compressed = Some(joined);
+        state.lifted = None;
+        state.segments_done += 1;
+    }
+
+    // Compress assumptions and resolve them.
+    for assumption in &receipt.assumptions[state.assumptions_done..] {
+        let assumption = match assumption {
+            InnerReceipt::Succinct(assumption) => assumption.clone(),
+            InnerReceipt::Composite(assumption) => {
+                composite_to_succinct(prover, assumption, lift_cache, retain_claim_tree)?
+            }
+            InnerReceipt::Fake { .. } => bail!(
+                "compressing composite receipts with fake receipt assumptions is not supported"
+            ),
+            InnerReceipt::Compact(_) => bail!(
+                "compressing composite receipts with Compact receipt assumptions is not supported"
+            ),
+        };
+        let conditional = state.compressed.as_ref().unwrap();
+        let mut resolved = prover.resolve(conditional, &assumption)?;
+        if retain_claim_tree {
+            resolved.claim_tree = Some(ClaimTree::Resolve {
+                claim: resolved.claim.clone(),
+                conditional: Box::new(ClaimTree::of(state.compressed.take().unwrap())),
+                assumption: Box::new(ClaimTree::of(assumption)),
+            });
+        }
+        state.compressed = Some(resolved);
+        state.assumptions_done += 1;
+    }
+
+    Ok(state.compressed.clone().unwrap())
+}
+
+/// Return true if any hook of the [Session] cancels proving before the given [Segment].
+fn cancelled(session: &Session, segment: &Segment) -> bool {
+    session
+        .hooks
+        .iter()
+        .any(|hook| hook.check_prove_segment(segment).is_break())
+}
+
+/// Lift a [SegmentReceipt], through the given cache if any.
+fn lift_cached<P: ProverServer + ?Sized>(
+    prover: &P,
+    lift_cache: Option<&LiftCache>,
+    receipt: &SegmentReceipt,
+) -> Result<SuccinctReceipt> {
+    match lift_cache {
+        Some(lift_cache) => lift_cache.get_or_lift(receipt, |receipt| prover.lift(receipt)),
+        None => prover.lift(receipt),
+    }
+}
+
+/// Return the kind of receipt that proving with the given [ProverOpts] should produce, taking
+/// [ProverOpts::stop_after] into account.
+fn target_receipt_kind(opts: &ProverOpts) -> ReceiptKind {
+    match opts.stop_after {
+        Some(kind) if compression_level(kind) < compression_level(opts.receipt_kind) => kind,
+        _ => opts.receipt_kind,
+    }
+}
+
+/// Order [ReceiptKind]s from least to most compressed.
+fn compression_level(kind: ReceiptKind) -> u8 {
+    match kind {
+        ReceiptKind::Composite => 0,
+        ReceiptKind::Succinct => 1,
+        ReceiptKind::Groth16 => 2,
+    }
+}
+
+/// An error returned by [ProverServer::prove_session] that carries partial results.
+///
+/// Returned wrapped in an [anyhow::Error]; use [anyhow::Error::downcast] to recover it.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ProveError {
+    /// Proving stopped because [ProverOpts::wall_clock_timeout] elapsed before every segment in
+    /// the session was proven.
+    TimedOut {
+        /// Receipts for the segments that were proven before the timeout, in session order.
+        segments_completed: Vec<SegmentReceipt>,
+    },
+    /// One or more segments failed to prove while [ProverOpts::continue_on_segment_error] was
+    /// set, so every segment was attempted.
+    SegmentsFailed {
+        /// The index and error of each segment that failed, in session order.
+        failures: Vec<(u32, anyhow::Error)>,
+    },
+    /// Proving stopped because the next segment would have taken the session past
+    /// [ProverOpts::max_total_cycles].
+    CycleLimitExceeded {
+        /// The cycles of the segments proven before stopping.
+        cycles_consumed: u64,
+        /// Receipts for the segments that were proven before stopping, in session order.
+        segments_completed: Vec<SegmentReceipt>,
+    },
+    /// Proving stopped because a session hook returned [std::ops::ControlFlow::Break] from
+    /// [crate::SessionEvents::check_prove_segment].
+    Cancelled {
+        /// Receipts for the segments that were proven before cancelling, in session order.
+        ///
+        /// This is empty when returned by [ProverServer::prove_session_streaming], which has
+        /// already yielded those receipts.
+        segments_completed: Vec<SegmentReceipt>,
+    },
+}
+
+impl core::fmt::Display for ProveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ProveError::TimedOut { segments_completed } => write!(
+                f,
+                "proving timed out after {} segments",
+                segments_completed.len()
+            ),
+            ProveError::SegmentsFailed { failures } => {
+                write!(f, "{} segments failed to prove", failures.len())?;
+                for (index, err) in failures {
+                    write!(f, "; segment {index}: {err}")?;
+                }
+                Ok(())
+            }
+            ProveError::CycleLimitExceeded {
+                cycles_consumed,
+                segments_completed,
+            } => write!(
+                f,
+                "proving stopped at the cycle limit after {} segments ({cycles_consumed} cycles)",
+                segments_completed.len()
+            ),
+            ProveError::Cancelled { segments_completed } => write!(
+                f,
+                "proving was cancelled by a session hook after {} segments",
+                segments_completed.len()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProveError {}
+
 #[cfg(feature = "cuda")]
 mod cuda {
     use std::rc::Rc;
@@ -228,7 +676,7 @@ mod cuda {
                 Ok(Rc::new(ProverImpl::new(
                     "cuda",
                     HalPair { hal, circuit_hal },
-                    opts.receipt_kind.clone(),
+                    opts.clone(),
                 )))
             }
             "poseidon2" => {
@@ -237,7 +685,7 @@ mod cuda {
                 Ok(Rc::new(ProverImpl::new(
                     "cuda",
                     HalPair { hal, circuit_hal },
-                    opts.receipt_kind.clone(),
+                    opts.clone(),
                 )))
             }
             _ => bail!("Unsupported hashfn: {}", opts.hashfn),
@@ -266,7 +714,7 @@ mod metal {
                 Ok(Rc::new(ProverImpl::new(
                     "metal",
                     HalPair { hal, circuit_hal },
-                    opts.receipt_kind.clone(),
+                    opts.clone(),
                 )))
             }
             "poseidon2" => {
@@ -275,7 +723,7 @@ mod metal {
                 Ok(Rc::new(ProverImpl::new(
                     "metal",
                     HalPair { hal, circuit_hal },
-                    opts.receipt_kind.clone(),
+                    opts.clone(),
                 )))
             }
             _ => bail!("Unsupported hashfn: {}", opts.hashfn),
@@ -306,7 +754,7 @@ mod cpu {
         let hal = Rc::new(CpuHal::new(suite));
         let circuit_hal = Rc::new(CpuCircuitHal::new());
         let hal_pair = HalPair { hal, circuit_hal };
-        Ok(Rc::new(ProverImpl::new("cpu", hal_pair, opts.receipt_kind)))
+        Ok(Rc::new(ProverImpl::new("cpu", hal_pair, opts.clone())))
     }
 }
 
@@ -315,7 +763,7 @@ mod cpu {
 pub fn get_prover_server(opts: &ProverOpts) -> Result<Rc<dyn ProverServer>> {
     if is_dev_mode() {
         eprintln!("WARNING: proving in dev mode. This will not generate valid, secure proofs.");
-        return Ok(Rc::new(DevModeProver));
+        return Ok(Rc::new(DevModeProver::new(opts.clone())));
     }
 
     cfg_if! {
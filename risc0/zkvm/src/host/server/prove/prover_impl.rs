@@ -12,20 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use anyhow::{bail, Result};
+use std::time::Instant;
+
+use anyhow::{bail, ensure, Result};
+use risc0_circuit_rv32im::prove::segment::Segment as CircuitSegment;
 use risc0_core::field::baby_bear::{BabyBear, Elem, ExtElem};
-use risc0_zkp::hal::{CircuitHal, Hal};
+use risc0_zkp::{
+    hal::{CircuitHal, Hal},
+    MAX_CYCLES_PO2, MIN_CYCLES_PO2,
+};
 
-use super::{HalPair, ProverServer};
+use super::{
+    cancelled, composite_to_succinct, target_receipt_kind, HalPair, ProveError, ProverServer,
+};
 use crate::{
     host::{
         client::prove::ReceiptKind,
-        prove_info::ProveInfo,
+        prove_info::{ProveInfo, SegmentProveStats},
         receipt::{InnerReceipt, SegmentReceipt, SuccinctReceipt},
         recursion::{identity_p254, join, lift, resolve},
     },
     sha::Digestible,
-    CompositeReceipt, Receipt, Segment, Session, VerifierContext,
+    CompositeReceipt, ProverOpts, Receipt, Segment, Session, VerifierContext,
 };
 
 /// An implementation of a Prover that runs locally.
@@ -36,7 +44,7 @@ where
 {
     name: String,
     hal_pair: HalPair<H, C>,
-    receipt_kind: ReceiptKind,
+    opts: ProverOpts,
 }
 
 impl<H, C> ProverImpl<H, C>
@@ -44,14 +52,57 @@ where
     H: Hal<Field = BabyBear, Elem = Elem, ExtElem = ExtElem>,
     C: CircuitHal<H>,
 {
-    /// Construct a [ProverImpl] with the given name and [HalPair].
-    pub fn new(name: &str, hal_pair: HalPair<H, C>, receipt_kind: ReceiptKind) -> Self {
+    /// Construct a [ProverImpl] with the given name, [HalPair] and [ProverOpts].
+    pub fn new(name: &str, hal_pair: HalPair<H, C>, opts: ProverOpts) -> Self {
         Self {
             name: name.to_string(),
             hal_pair,
-            receipt_kind,
+            opts,
         }
     }
+
+    /// Prove the specified [Segment], also returning the resources used to prove it.
+    fn prove_segment_with_stats(
+        &self,
+        ctx: &VerifierContext,
+        segment: &Segment,
+    ) -> Result<(SegmentReceipt, SegmentProveStats)> {
+        use risc0_circuit_rv32im::prove::{engine::SegmentProverImpl, SegmentProver as _};
+
+        use crate::host::receipt::segment::decode_receipt_claim_from_seal;
+
+        let start = Instant::now();
+        let hashfn = self.hal_pair.hal.get_hash_suite().name.clone();
+
+        let prover =
+            SegmentProverImpl::new(self.hal_pair.hal.clone(), self.hal_pair.circuit_hal.clone());
+        let padded;
+        let inner = match self.opts.segment_po2 {
+            Some(po2) => {
+                padded = pad_segment(segment, po2)?;
+                &padded
+            }
+            None => &segment.inner,
+        };
+        let (seal, stats) = prover.prove_segment_with_stats(inner)?;
+
+        let mut claim = decode_receipt_claim_from_seal(&seal)?;
+        claim.output = segment.output.clone().into();
+
+        let receipt = SegmentReceipt {
+            seal,
+            index: segment.index,
+            hashfn,
+            claim,
+        };
+        receipt.verify_integrity_with_context(ctx)?;
+
+        let stats = SegmentProveStats {
+            witness_peak_bytes: stats.witness_peak_bytes as u64,
+            duration: start.elapsed(),
+        };
+        Ok((receipt, stats))
+    }
 }
 
 impl<H, C> ProverServer for ProverImpl<H, C>
@@ -67,22 +118,69 @@ where
             session.journal.as_ref().map(hex::encode),
             session.segments.len()
         );
+        let start = Instant::now();
         let mut segments = Vec::new();
-        for segment_ref in session.segments.iter() {
-            let segment = segment_ref.resolve()?;
-            for hook in &session.hooks {
-                hook.on_pre_prove_segment(&segment);
+        let mut segment_stats = Vec::new();
+        let mut failures = Vec::new();
+        let mut cycles_consumed = 0;
+        for (index, segment_ref) in session.segments.iter().enumerate() {
+            if let Some(timeout) = self.opts.wall_clock_timeout {
+                if !segments.is_empty() && start.elapsed() >= timeout {
+                    return Err(ProveError::TimedOut {
+                        segments_completed: segments,
+                    }
+                    .into());
+                }
+            }
+            let segment = segment_ref.resolve();
+            if let (Some(limit), Ok(segment)) = (self.opts.max_total_cycles, &segment) {
+                let po2 = self.opts.segment_po2.map_or(segment.po2(), |po2| po2 as usize);
+                let cycles = 1u64 << po2;
+                if cycles_consumed + cycles > limit {
+                    return Err(ProveError::CycleLimitExceeded {
+                        cycles_consumed,
+                        segments_completed: segments,
+                    }
+                    .into());
+                }
+                cycles_consumed += cycles;
             }
-            segments.push(self.prove_segment(ctx, &segment)?);
-            for hook in &session.hooks {
-                hook.on_post_prove_segment(&segment);
+            if matches!(&segment, Ok(segment) if cancelled(session, segment)) {
+                return Err(ProveError::Cancelled {
+                    segments_completed: segments,
+                }
+                .into());
             }
+            let result = segment.and_then(|segment| {
+                for hook in &session.hooks {
+                    hook.on_pre_prove_segment(&segment);
+                }
+                let result = self.prove_segment_with_stats(ctx, &segment)?;
+                for hook in &session.hooks {
+                    hook.on_post_prove_segment(&segment);
+                }
+                Ok(result)
+            });
+            match result {
+                Ok((receipt, stats)) => {
+                    segments.push(receipt);
+                    segment_stats.push(stats);
+                }
+                Err(err) if self.opts.continue_on_segment_error => {
+                    tracing::debug!("segment {index} failed to prove: {err}");
+                    failures.push((index as u32, err));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        if !failures.is_empty() {
+            return Err(ProveError::SegmentsFailed { failures }.into());
         }
         // TODO(#982): Support unresolved assumptions here.
         let assumptions = session
             .assumptions
             .iter()
-            .map(|x| Ok(x.as_receipt()?.inner.clone()))
+            .map(|x| Ok(session.assumption_receipt(x)?.inner))
             .collect::<Result<Vec<_>>>()?;
         let composite_receipt = CompositeReceipt {
             segments,
@@ -104,20 +202,30 @@ where
         }
 
         // Compress the receipt to the requested level.
-        let receipt = match self.receipt_kind {
+        let receipt = match target_receipt_kind(&self.opts) {
             ReceiptKind::Composite => Receipt::new(
                 InnerReceipt::Composite(composite_receipt),
                 session.journal.clone().unwrap_or_default().bytes,
             ),
             ReceiptKind::Succinct => {
-                let succinct_receipt = self.compsite_to_succinct(&composite_receipt)?;
+                let succinct_receipt = composite_to_succinct(
+                    self,
+                    &composite_receipt,
+                    self.opts.lift_cache.as_deref(),
+                    self.opts.retain_claim_tree,
+                )?;
                 Receipt::new(
                     InnerReceipt::Succinct(succinct_receipt),
                     session.journal.clone().unwrap_or_default().bytes,
                 )
             }
-            ReceiptKind::Compact => {
-                let succinct_receipt = self.compsite_to_succinct(&composite_receipt)?;
+            ReceiptKind::Groth16 => {
+                let succinct_receipt = composite_to_succinct(
+                    self,
+                    &composite_receipt,
+                    self.opts.lift_cache.as_deref(),
+                    false,
+                )?;
                 let compact_receipt = self.succinct_to_compact(&succinct_receipt)?;
                 Receipt::new(
                     InnerReceipt::Compact(compact_receipt),
@@ -142,32 +250,28 @@ where
         Ok(ProveInfo {
             receipt,
             stats: session.stats(),
+            segment_stats,
+            proving_time: start.elapsed(),
         })
     }
 
-    fn prove_segment(&self, ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt> {
-        use risc0_circuit_rv32im::prove::{engine::SegmentProverImpl, SegmentProver as _};
-
-        use crate::host::receipt::segment::decode_receipt_claim_from_seal;
-
-        let hashfn = self.hal_pair.hal.get_hash_suite().name.clone();
-
-        let prover =
-            SegmentProverImpl::new(self.hal_pair.hal.clone(), self.hal_pair.circuit_hal.clone());
-        let seal = prover.prove_segment(&segment.inner)?;
-
-        let mut claim = decode_receipt_claim_from_seal(&seal)?;
-        claim.output = segment.output.clone().into();
-
-        let receipt = SegmentReceipt {
-            seal,
-            index: segment.index,
-            hashfn,
-            claim,
-        };
-        receipt.verify_integrity_with_context(ctx)?;
+    fn prove_session_opts(
+        &self,
+        ctx: &VerifierContext,
+        session: &Session,
+        opts: &ProverOpts,
+    ) -> Result<ProveInfo> {
+        ensure!(
+            opts.hashfn == self.opts.hashfn,
+            "per-call hashfn {} does not match the prover's hashfn {}",
+            opts.hashfn,
+            self.opts.hashfn
+        );
+        ProverImpl::new(&self.name, self.hal_pair.clone(), opts.clone()).prove_session(ctx, session)
+    }
 
-        Ok(receipt)
+    fn prove_segment(&self, ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt> {
+        Ok(self.prove_segment_with_stats(ctx, segment)?.0)
     }
 
     fn lift(&self, receipt: &SegmentReceipt) -> Result<SuccinctReceipt> {
@@ -189,4 +293,27 @@ where
     fn identity_p254(&self, a: &SuccinctReceipt) -> Result<SuccinctReceipt> {
         identity_p254(a)
     }
+
+    fn effective_opts(&self) -> ProverOpts {
+        self.opts.clone()
+    }
+}
+
+/// Copy the given [Segment], padding its trace to `2^po2` cycles.
+fn pad_segment(segment: &Segment, po2: u32) -> Result<CircuitSegment> {
+    let po2 = po2 as usize;
+    ensure!(
+        (MIN_CYCLES_PO2..MAX_CYCLES_PO2).contains(&po2),
+        "segment_po2 {po2} is outside the supported range {MIN_CYCLES_PO2}..{MAX_CYCLES_PO2}"
+    );
+    ensure!(
+        segment.po2() <= po2,
+        "segment {} was executed with po2 {}, which does not fit in segment_po2 {po2}; \
+         re-execute the session with a smaller segment limit",
+        segment.index,
+        segment.po2()
+    );
+    let mut inner = segment.inner.clone();
+    inner.po2 = po2;
+    Ok(inner)
 }
@@ -0,0 +1,122 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{Read, Write};
+
+use anyhow::Result;
+use risc0_binfmt::{MemoryImage, SystemState};
+use serde::{Deserialize, Serialize};
+
+use super::get_prover_server;
+use crate::{
+    host::prove_info::ProveInfo, sha::Digest, Assumption, ExitCode, ProverOpts, Segment,
+    SegmentRef, Session, SimpleSegmentRef, VerifierContext,
+};
+
+/// A self-contained bundle for reproducing a call to
+/// [ProverServer::prove_session](crate::ProverServer::prove_session) elsewhere.
+///
+/// The bundle holds every resolved [Segment] of the [Session] along with the
+/// [ProverOpts], so it can be attached to a bug report when proving fails and
+/// replayed with [ReproBundle::reproduce]. Session hooks are not included.
+#[derive(Serialize, Deserialize)]
+pub struct ReproBundle {
+    /// Version of the `risc0-zkvm` crate that created the bundle.
+    pub version: String,
+
+    /// The options the prover was configured with.
+    pub opts: ProverOpts,
+
+    segments: Vec<Segment>,
+    input: Digest,
+    journal: Option<Vec<u8>>,
+    exit_code: ExitCode,
+    post_image: MemoryImage,
+    assumptions: Vec<Assumption>,
+    user_cycles: u64,
+    total_cycles: u64,
+    pre_state: SystemState,
+    post_state: SystemState,
+}
+
+impl ReproBundle {
+    /// Capture the given [Session] and [ProverOpts], resolving every segment.
+    pub fn new(opts: &ProverOpts, session: &Session) -> Result<Self> {
+        let segments = session
+            .segments
+            .iter()
+            .map(|segment_ref| segment_ref.resolve())
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            opts: opts.clone(),
+            segments,
+            input: session.input,
+            journal: session.journal.as_ref().map(|journal| journal.bytes.clone()),
+            exit_code: session.exit_code,
+            post_image: session.post_image.clone(),
+            assumptions: session.assumptions.clone(),
+            user_cycles: session.user_cycles,
+            total_cycles: session.total_cycles,
+            pre_state: session.pre_state.clone(),
+            post_state: session.post_state.clone(),
+        })
+    }
+
+    /// Serialize this bundle to the given writer.
+    pub fn write<W: Write>(&self, writer: W) -> Result<()> {
+        Ok(bincode::serialize_into(writer, self)?)
+    }
+
+    /// Deserialize a bundle previously written with [ReproBundle::write].
+    pub fn read<R: Read>(reader: R) -> Result<Self> {
+        Ok(bincode::deserialize_from(reader)?)
+    }
+
+    /// Rebuild the captured [Session].
+    pub fn session(&self) -> Session {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| Box::new(SimpleSegmentRef::new(segment.clone())) as Box<dyn SegmentRef>)
+            .collect();
+        Session::new(
+            segments,
+            self.input,
+            self.journal.clone(),
+            self.exit_code,
+            self.post_image.clone(),
+            self.assumptions.clone(),
+            self.user_cycles,
+            self.total_cycles,
+            self.pre_state.clone(),
+            self.post_state.clone(),
+        )
+    }
+
+    /// Read a bundle from the given reader and prove its session again with the
+    /// captured [ProverOpts].
+    pub fn reproduce<R: Read>(reader: R) -> Result<ProveInfo> {
+        let bundle = Self::read(reader)?;
+        if bundle.version != env!("CARGO_PKG_VERSION") {
+            tracing::warn!(
+                "reproducing a bundle created by risc0-zkvm {} with {}",
+                bundle.version,
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+        get_prover_server(&bundle.opts)?
+            .prove_session(&VerifierContext::default(), &bundle.session())
+    }
+}
@@ -12,9 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::rc::Rc;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use risc0_binfmt::MemoryImage;
 use risc0_circuit_rv32im::prove::{emu::testutil, hal::cpu::CpuCircuitHal};
 use risc0_zkp::{
@@ -22,28 +29,28 @@ use risc0_zkp::{
     hal::cpu::CpuHal,
     verify::VerificationError,
 };
-use risc0_zkvm_methods::{multi_test::MultiTestSpec, MULTI_TEST_ELF, MULTI_TEST_ID};
+use risc0_zkvm_methods::{
+    multi_test::MultiTestSpec, HELLO_COMMIT_ELF, MULTI_TEST_ELF, MULTI_TEST_ID,
+};
 use risc0_zkvm_platform::{memory, PAGE_SIZE, WORD_SIZE};
 use test_log::test;
 
-use super::{get_prover_server, HalPair, ProverImpl};
+use super::{
+    get_prover_server, CompressState, DevModeProver, HalPair, ProveError, ProverImpl,
+    RemoteClient, RemoteStatus, ReproBundle, UploadingProver,
+};
 use crate::{
     host::server::testutils,
     serde::{from_slice, to_vec},
-    ExecutorEnv, ExecutorImpl, ExitCode, ProveInfo, ProverOpts, ProverServer, Receipt, ReceiptKind,
-    Session, VerifierContext,
+    sha::Digestible,
+    verify_succinct, Assumptions, CompositeReceipt, ExecutorEnv, ExecutorError, ExecutorImpl,
+    ExitCode, InnerReceipt, LiftCache, MaybePruned, Output, ProveInfo, ProverOpts, ProverServer,
+    Receipt, ReceiptClaim, ReceiptKind, Segment, SegmentReceipt, SegmentRef, Session,
+    SimpleSegmentRef, SuccinctReceipt, VerificationOutcome, VerifierContext,
 };
 
-fn prover_opts_fast() -> ProverOpts {
-    ProverOpts {
-        hashfn: "sha-256".to_string(),
-        prove_guest_errors: false,
-        receipt_kind: ReceiptKind::Composite,
-    }
-}
-
 fn prove_session_fast(session: &Session) -> Receipt {
-    let prover = get_prover_server(&prover_opts_fast()).unwrap();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
     prover
         .prove_session(&VerifierContext::default(), session)
         .unwrap()
@@ -60,6 +67,13 @@ fn prove_nothing(hashfn: &str) -> Result<ProveInfo> {
         hashfn: hashfn.to_string(),
         prove_guest_errors: false,
         receipt_kind: ReceiptKind::Composite,
+        retain_claim_tree: false,
+        wall_clock_timeout: None,
+        stop_after: None,
+        continue_on_segment_error: false,
+        segment_po2: None,
+        max_total_cycles: None,
+        lift_cache: None,
     };
     get_prover_server(&opts).unwrap().prove(env, MULTI_TEST_ELF)
 }
@@ -72,14 +86,263 @@ fn prove_nothing_succinct() {
         .build()
         .unwrap();
     let opts = ProverOpts::succinct();
-    get_prover_server(&opts)
+    let receipt = get_prover_server(&opts)
         .unwrap()
         .prove(env, MULTI_TEST_ELF)
         .unwrap()
-        .receipt
-        .inner
-        .succinct()
-        .unwrap(); // ensure that we got a succinct receipt.
+        .receipt;
+    let succinct = receipt.inner.succinct().unwrap(); // ensure that we got a succinct receipt.
+
+    let ctx = VerifierContext::default();
+    let journal_digest = receipt.journal.digest();
+    verify_succinct(succinct, MULTI_TEST_ID.into(), journal_digest, &ctx).unwrap();
+    assert_eq!(
+        verify_succinct(succinct, Digest::ZERO, journal_digest, &ctx).unwrap_err(),
+        VerificationError::ImageVerificationError
+    );
+
+    let decoded = Receipt::from_bytes(&receipt.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded, receipt);
+    decoded.verify(MULTI_TEST_ID).unwrap();
+}
+
+#[test]
+fn verify_or_dev() {
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    assert_eq!(
+        receipt.verify_or_dev(MULTI_TEST_ID),
+        VerificationOutcome::Verified
+    );
+}
+
+#[test]
+fn convert_receipt_kind() {
+    let prover = get_prover_server(&ProverOpts::composite()).unwrap();
+    let composite = prove_nothing("poseidon2").unwrap().receipt;
+
+    let receipt = composite
+        .convert_to(ReceiptKind::Composite, prover.as_ref())
+        .unwrap();
+    receipt.inner.composite().unwrap();
+
+    let succinct = composite
+        .convert_to(ReceiptKind::Succinct, prover.as_ref())
+        .unwrap();
+    succinct.inner.succinct().unwrap();
+    succinct.verify(MULTI_TEST_ID).unwrap();
+
+    let receipt = succinct
+        .convert_to(ReceiptKind::Succinct, prover.as_ref())
+        .unwrap();
+    receipt.inner.succinct().unwrap();
+
+    let err = succinct
+        .convert_to(ReceiptKind::Composite, prover.as_ref())
+        .unwrap_err();
+    assert!(err.to_string().contains("less compressed"), "{err}");
+}
+
+#[test]
+fn prove_info_prometheus() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    let info = get_prover_server(&ProverOpts::fast())
+        .unwrap()
+        .prove_session(&VerifierContext::default(), &session)
+        .unwrap();
+
+    let text = info.to_prometheus(&[("job", "do_nothing"), ("tenant", "a\"b")]);
+    for name in [
+        "risc0_segments",
+        "risc0_total_cycles",
+        "risc0_user_cycles",
+        "risc0_proving_seconds",
+        "risc0_segment_proving_seconds",
+    ] {
+        assert!(text.contains(&format!("# TYPE {name} gauge")), "{text}");
+    }
+    let labels = r#"{job="do_nothing",tenant="a\"b"}"#;
+    let total_cycles = format!("risc0_total_cycles{labels} {}", session.total_cycles);
+    assert!(text.contains(&total_cycles), "{text}");
+    assert!(text.contains(&format!("risc0_segments{labels} 1")), "{text}");
+    let proving_seconds = info.proving_time.as_secs_f64();
+    let proving_seconds = format!("risc0_proving_seconds{labels} {proving_seconds}");
+    assert!(text.contains(&proving_seconds), "{text}");
+
+    assert_eq!(info.segment_stats.len(), 1);
+    let segment_seconds = info.segment_stats[0].duration.as_secs_f64();
+    let segment_labels = r#"{job="do_nothing",tenant="a\"b",segment="0"}"#;
+    let segment_seconds =
+        format!("risc0_segment_proving_seconds{segment_labels} {segment_seconds}");
+    assert!(text.contains(&segment_seconds), "{text}");
+}
+
+#[derive(Default)]
+struct MockRemoteClient {
+    info: RefCell<Option<ProveInfo>>,
+    input: Rc<RefCell<Vec<u8>>>,
+    polls: Rc<Cell<usize>>,
+}
+
+impl RemoteClient for MockRemoteClient {
+    fn submit(&self, _elf: &[u8], input: &[u8]) -> Result<String> {
+        *self.input.borrow_mut() = input.to_vec();
+        Ok("job-0".to_string())
+    }
+
+    fn status(&self, job_id: &str) -> Result<RemoteStatus> {
+        assert_eq!(job_id, "job-0");
+        self.polls.set(self.polls.get() + 1);
+        if self.polls.get() == 1 {
+            return Ok(RemoteStatus::Running);
+        }
+        Ok(RemoteStatus::Succeeded(self.info.take().unwrap()))
+    }
+}
+
+#[test]
+fn uploading_prover() {
+    let client = MockRemoteClient {
+        info: RefCell::new(Some(prove_nothing("sha-256").unwrap())),
+        ..Default::default()
+    };
+    let input = client.input.clone();
+    let polls = client.polls.clone();
+    let prover = UploadingProver::new(client).with_poll_interval(Duration::ZERO);
+
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    let receipt = prover.prove(env, MULTI_TEST_ELF).unwrap().receipt;
+    receipt.verify(MULTI_TEST_ID).unwrap();
+
+    assert_eq!(polls.get(), 2);
+    let expected = to_vec(&MultiTestSpec::DoNothing).unwrap();
+    assert_eq!(
+        input.borrow().as_slice(),
+        bytemuck::cast_slice::<u32, u8>(&expected)
+    );
+}
+
+#[test]
+fn uploading_prover_rejects_unsent_settings() {
+    let client = MockRemoteClient::default();
+    let input = client.input.clone();
+    let prover = UploadingProver::new(client).with_poll_interval(Duration::ZERO);
+
+    let env = ExecutorEnv::builder()
+        .env_var("KEY", "VALUE")
+        .segment_limit_po2(14)
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    let err = prover.prove(env, MULTI_TEST_ELF).err().unwrap().to_string();
+    assert!(err.contains("env_vars, segment_limit_po2"), "{err}");
+    assert!(input.borrow().is_empty());
+}
+
+#[test]
+fn journal_len() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::Echo {
+            bytes: b"journal!".to_vec(),
+        })
+        .unwrap()
+        .build()
+        .unwrap();
+    let receipt = get_prover_server(&ProverOpts::fast())
+        .unwrap()
+        .prove(env, MULTI_TEST_ELF)
+        .unwrap()
+        .receipt;
+    assert_eq!(receipt.journal_len(), 8);
+    assert_eq!(receipt.journal_len(), receipt.journal.bytes.len());
+}
+
+#[test]
+fn verify_with_journal_digest() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::Echo {
+            bytes: b"journal!".to_vec(),
+        })
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut receipt = get_prover_server(&ProverOpts::fast())
+        .unwrap()
+        .prove(env, MULTI_TEST_ELF)
+        .unwrap()
+        .receipt;
+
+    // The journal is not needed to verify against its digest.
+    let journal_digest = receipt.journal.digest();
+    receipt.journal.bytes.clear();
+    receipt
+        .verify_with_journal_digest(MULTI_TEST_ID, journal_digest)
+        .unwrap();
+    assert!(matches!(
+        receipt
+            .verify_with_journal_digest(MULTI_TEST_ID, Digest::ZERO)
+            .unwrap_err(),
+        VerificationError::JournalDigestMismatch
+    ));
+}
+
+#[test]
+fn allowed_hashfns() {
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    let ctx = VerifierContext::default().with_allowed_hashfns(&["sha-256"]);
+    receipt.verify_with_context(&ctx, MULTI_TEST_ID).unwrap();
+
+    let ctx = VerifierContext::default().with_allowed_hashfns(&["poseidon2"]);
+    assert_eq!(
+        receipt.verify_with_context(&ctx, MULTI_TEST_ID).unwrap_err(),
+        VerificationError::InvalidHashSuite
+    );
+}
+
+#[test]
+fn verify_with_prover_opts() {
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    receipt
+        .verify_with_prover_opts(MULTI_TEST_ID, &ProverOpts::fast())
+        .unwrap();
+    assert_eq!(
+        receipt
+            .verify_with_prover_opts(MULTI_TEST_ID, &ProverOpts::composite())
+            .unwrap_err(),
+        VerificationError::InvalidHashSuite
+    );
+}
+
+#[test]
+fn min_soundness_bits() {
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    let ctx = VerifierContext::default().with_min_soundness_bits(0.0);
+    receipt.verify_with_context(&ctx, MULTI_TEST_ID).unwrap();
+
+    let ctx = VerifierContext::default().with_min_soundness_bits(1000.0);
+    assert!(matches!(
+        receipt.verify_with_context(&ctx, MULTI_TEST_ID).unwrap_err(),
+        VerificationError::InsufficientSoundness { required, .. } if required == 1000.0
+    ));
+}
+
+#[test]
+fn verify_against_elf() {
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    receipt.verify_against_elf(MULTI_TEST_ELF).unwrap();
+    receipt.verify_against_elf(HELLO_COMMIT_ELF).unwrap_err();
 }
 
 #[test]
@@ -98,10 +361,27 @@ fn hashfn_blake2b() {
         .unwrap()
         .build()
         .unwrap();
-    let prover = ProverImpl::new("cpu:blake2b", hal_pair, ReceiptKind::Composite);
+    let prover = ProverImpl::new("cpu:blake2b", hal_pair, ProverOpts::default());
     prover.prove(env, MULTI_TEST_ELF).unwrap();
 }
 
+#[test]
+fn stop_after() {
+    let opts = ProverOpts::succinct().with_stop_after(ReceiptKind::Composite);
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    let receipt = get_prover_server(&opts)
+        .unwrap()
+        .prove(env, MULTI_TEST_ELF)
+        .unwrap()
+        .receipt;
+    receipt.inner.composite().unwrap();
+    receipt.verify(MULTI_TEST_ID).unwrap();
+}
+
 #[test]
 fn receipt_serde() {
     let receipt = prove_nothing("sha-256").unwrap().receipt;
@@ -111,6 +391,95 @@ fn receipt_serde() {
     decoded.verify(MULTI_TEST_ID).unwrap();
 }
 
+#[test]
+fn receipt_metadata() {
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    let mut tagged = receipt.clone();
+    assert_eq!(tagged.set_metadata("job_id", "1234"), None);
+    tagged.set_metadata("tenant", "acme");
+
+    let decoded = Receipt::from_bytes(&tagged.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded.get_metadata("job_id"), Some("1234"));
+    assert_eq!(decoded.get_metadata("tenant"), Some("acme"));
+    assert_eq!(decoded.get_metadata("missing"), None);
+    decoded.verify(MULTI_TEST_ID).unwrap();
+
+    assert_eq!(decoded.claim().unwrap(), receipt.claim().unwrap());
+    assert!(decoded.seal_eq(&receipt));
+    assert_ne!(decoded, receipt);
+
+    // Metadata is not part of the serde encoding.
+    let encoded: Vec<u32> = to_vec(&tagged).unwrap();
+    assert_eq!(encoded, to_vec(&receipt).unwrap());
+    assert_eq!(from_slice::<Receipt, _>(&encoded).unwrap(), receipt);
+}
+
+#[test]
+fn receipt_bytes() {
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    assert_eq!(receipt.kind(), ReceiptKind::Composite);
+
+    let decoded = Receipt::from_bytes(&receipt.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded, receipt);
+    decoded.verify(MULTI_TEST_ID).unwrap();
+}
+
+#[test]
+fn receipt_baseline_encoding() {
+    // Receipts were encoded as a struct of just the inner receipt and the journal, which
+    // encodes the same as this tuple.
+    let receipt = prove_nothing("sha-256").unwrap().receipt;
+    let baseline = (&receipt.inner, &receipt.journal);
+
+    let words: Vec<u32> = to_vec(&baseline).unwrap();
+    let decoded: Receipt = from_slice(&words).unwrap();
+    assert_eq!(decoded, receipt);
+    decoded.verify(MULTI_TEST_ID).unwrap();
+
+    let bytes = bincode::serialize(&baseline).unwrap();
+    let decoded: Receipt = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, receipt);
+    decoded.verify(MULTI_TEST_ID).unwrap();
+}
+
+#[test]
+fn prover_opts_baseline_encoding() {
+    // ProverOpts used to have only these three fields.
+    let json = r#"{"hashfn":"sha-256","prove_guest_errors":true,"receipt_kind":"Succinct"}"#;
+    let opts: ProverOpts = serde_json::from_str(json).unwrap();
+    assert_eq!(opts.hashfn, "sha-256");
+    assert!(opts.prove_guest_errors);
+    assert_eq!(opts.receipt_kind, ReceiptKind::Succinct);
+
+    let default = ProverOpts::default();
+    assert_eq!(opts.retain_claim_tree, default.retain_claim_tree);
+    assert_eq!(opts.wall_clock_timeout, default.wall_clock_timeout);
+    assert_eq!(opts.stop_after, default.stop_after);
+    assert_eq!(
+        opts.continue_on_segment_error,
+        default.continue_on_segment_error
+    );
+    assert_eq!(opts.segment_po2, default.segment_po2);
+    assert_eq!(opts.max_total_cycles, default.max_total_cycles);
+}
+
+#[test]
+fn mixed_hashfn_segments() {
+    let mut receipt = prove_nothing("sha-256").unwrap().receipt;
+    let InnerReceipt::Composite(ref mut composite) = receipt.inner else {
+        panic!("expected composite receipt");
+    };
+    let mut segment = composite.segments[0].clone();
+    segment.hashfn = "poseidon2".to_string();
+    composite.segments.push(segment);
+    assert_eq!(
+        receipt
+            .verify_integrity_with_context(&VerifierContext::default())
+            .unwrap_err(),
+        VerificationError::MixedHashFunctions
+    );
+}
+
 #[test]
 fn check_image_id() {
     let receipt = prove_nothing("sha-256").unwrap().receipt;
@@ -135,7 +504,7 @@ fn sha_basics() {
         let mut exec = ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap();
         let session = exec.run().unwrap();
         let receipt = prove_session_fast(&session);
-        hex::encode(Digest::try_from(receipt.journal.bytes).unwrap())
+        hex::encode(receipt.journal_decode::<Digest>().unwrap())
     }
 
     assert_eq!(
@@ -156,6 +525,33 @@ fn sha_basics() {
     );
 }
 
+#[test]
+fn run_and_prove() {
+    fn run_sha(msg: &str) -> String {
+        let env = ExecutorEnv::builder()
+            .write(&MultiTestSpec::ShaDigest { data: msg.into() })
+            .unwrap()
+            .build()
+            .unwrap();
+        let exec = ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap();
+        let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+        let receipt = exec
+            .run_and_prove(prover.as_ref(), &VerifierContext::default())
+            .unwrap()
+            .receipt;
+        hex::encode(receipt.journal_decode::<Digest>().unwrap())
+    }
+
+    assert_eq!(
+        run_sha("abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        run_sha("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
 #[test]
 fn sha_iter() {
     let input = MultiTestSpec::ShaDigestIter {
@@ -170,7 +566,7 @@ fn sha_iter() {
     let mut exec = ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap();
     let session = exec.run().unwrap();
     let receipt = prove_session_fast(&session);
-    let digest = Digest::try_from(receipt.journal.bytes).unwrap();
+    let digest = receipt.journal_decode::<Digest>().unwrap();
     assert_eq!(
         hex::encode(digest),
         "9d4d1940b5c0c6d09c10add9631806f9df9467884d3e9ce4a147113e27f5c02a"
@@ -247,15 +643,23 @@ fn memory_io() {
     assert_eq!(run_memio(&[(POS, 1)]).unwrap(), ExitCode::Halted(0));
 
     // Unaligned write is bad
-    let err = run_memio(&[(POS + 1001, 1)]).err().unwrap().to_string();
-    assert!(err.contains("StoreAddressMisaligned"), "{err}");
+    let err = run_memio(&[(POS + 1001, 1)]).err().unwrap();
+    assert!(err.to_string().contains("StoreAddressMisaligned"), "{err}");
+    assert!(matches!(
+        err.downcast_ref::<ExecutorError>(),
+        Some(ExecutorError::StoreAddressMisaligned { addr, .. }) if *addr == (POS + 1001) as u32
+    ));
 
     // Aligned read is fine
     assert_eq!(run_memio(&[(POS, 0)]).unwrap(), ExitCode::Halted(0));
 
     // Unaligned read is bad
-    let err = run_memio(&[(POS + 1, 0)]).err().unwrap().to_string();
-    assert!(err.contains("LoadAddressMisaligned"), "{err}");
+    let err = run_memio(&[(POS + 1, 0)]).err().unwrap();
+    assert!(err.to_string().contains("LoadAddressMisaligned"), "{err}");
+    assert!(matches!(
+        err.downcast_ref::<ExecutorError>(),
+        Some(ExecutorError::LoadAddressMisaligned { addr, .. }) if *addr == (POS + 1) as u32
+    ));
 }
 
 #[test]
@@ -296,42 +700,87 @@ fn session_events() {
     assert_eq!(on_post_prove_segment_flag.take(), true);
 }
 
+#[test]
+fn session_events_cancel() {
+    use std::ops::ControlFlow;
+
+    use crate::SessionEvents;
+
+    struct CancelAt(u32);
+
+    impl SessionEvents for CancelAt {
+        fn check_prove_segment(&self, segment: &Segment) -> ControlFlow<()> {
+            if segment.index == self.0 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    let mut session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+    session.add_hook(CancelAt(1));
+
+    let err = get_prover_server(&ProverOpts::fast())
+        .unwrap()
+        .prove_session(&VerifierContext::default(), &session)
+        .err()
+        .unwrap();
+    let ProveError::Cancelled { segments_completed } = err.downcast::<ProveError>().unwrap() else {
+        panic!("expected proving to be cancelled");
+    };
+    assert_eq!(segments_completed.len(), 1);
+}
+
 // These tests come from:
 // https://github.com/riscv-software-src/riscv-tests
 // They were built using the toolchain from:
 // https://github.com/risc0/toolchain/releases/tag/2022.03.25
 mod riscv {
+    use std::{collections::HashMap, sync::OnceLock};
+
     use super::prove_session_fast;
     use crate::{ExecutorEnv, ExecutorImpl};
 
-    fn run_test(test_name: &str) {
+    // The test ELFs by file name, decompressed once for all test cases.
+    fn test_elfs() -> &'static HashMap<String, Vec<u8>> {
         use std::io::Read;
 
         use flate2::read::GzDecoder;
         use tar::Archive;
 
-        let bytes = include_bytes!("../testdata/riscv-tests.tgz");
-        let gz = GzDecoder::new(&bytes[..]);
-        let mut tar = Archive::new(gz);
-        for entry in tar.entries().unwrap() {
-            let mut entry = entry.unwrap();
-            if !entry.header().entry_type().is_file() {
-                continue;
-            }
-            let path = entry.path().unwrap();
-            let filename = path.file_name().unwrap().to_str().unwrap();
-            if filename != test_name {
-                continue;
+        static ELFS: OnceLock<HashMap<String, Vec<u8>>> = OnceLock::new();
+        ELFS.get_or_init(|| {
+            let bytes = include_bytes!("../testdata/riscv-tests.tgz");
+            let gz = GzDecoder::new(&bytes[..]);
+            let mut tar = Archive::new(gz);
+            let mut elfs = HashMap::new();
+            for entry in tar.entries().unwrap() {
+                let mut entry = entry.unwrap();
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+                let path = entry.path().unwrap();
+                let filename = path.file_name().unwrap().to_str().unwrap().to_string();
+                let mut elf = Vec::new();
+                entry.read_to_end(&mut elf).unwrap();
+                elfs.insert(filename, elf);
             }
-            let mut elf = Vec::new();
-            entry.read_to_end(&mut elf).unwrap();
+            elfs
+        })
+    }
 
-            let env = ExecutorEnv::default();
-            let mut exec = ExecutorImpl::from_elf(env, &elf).unwrap();
-            let session = exec.run().unwrap();
+    fn run_test(test_name: &str) {
+        let elf = test_elfs()
+            .get(test_name)
+            .unwrap_or_else(|| panic!("no riscv test named {test_name} in archive"));
 
-            prove_session_fast(&session);
-        }
+        let env = ExecutorEnv::default();
+        let mut exec = ExecutorImpl::from_elf(env, elf).unwrap();
+        let session = exec.run().unwrap();
+
+        prove_session_fast(&session);
     }
 
     macro_rules! test_case {
@@ -404,15 +853,60 @@ fn pause_resume() {
     let session = exec.run().unwrap();
     assert_eq!(session.segments.len(), 1);
     assert_eq!(session.exit_code, ExitCode::Paused(0));
-    let receipt = prove_session_fast(&session);
-    let segments = &receipt.inner.composite().unwrap().segments;
+    let pause_receipt = prove_session_fast(&session);
+    let segments = &pause_receipt.inner.composite().unwrap().segments;
     assert_eq!(segments.len(), 1);
     assert_eq!(segments[0].index, 0);
 
     // Run until sys_halt
     let session = exec.run().unwrap();
     assert_eq!(session.exit_code, ExitCode::Halted(0));
-    prove_session_fast(&session);
+    let halt_receipt = prove_session_fast(&session);
+
+    let journal = Receipt::concat_journals(&[&pause_receipt, &halt_receipt]).unwrap();
+    let expected = [
+        pause_receipt.journal.bytes.as_slice(),
+        halt_receipt.journal.bytes.as_slice(),
+    ]
+    .concat();
+    assert_eq!(journal, expected);
+
+    // Out of order receipts do not form a pause/resume chain.
+    let err = Receipt::concat_journals(&[&halt_receipt, &pause_receipt]).unwrap_err();
+    assert!(err.to_string().contains("instead of a pause"), "{err}");
+}
+
+#[test]
+fn prove_info_merge() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::PauseResume(0))
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut exec = ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    let ctx = VerifierContext::default();
+
+    let session = exec.run().unwrap();
+    assert_eq!(session.exit_code, ExitCode::Paused(0));
+    let pause_info = prover.prove_session(&ctx, &session).unwrap();
+    let session = exec.run().unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    let halt_info = prover.prove_session(&ctx, &session).unwrap();
+
+    let segments = pause_info.stats.segments + halt_info.stats.segments;
+    let total_cycles = pause_info.stats.total_cycles + halt_info.stats.total_cycles;
+    let user_cycles = pause_info.stats.user_cycles + halt_info.stats.user_cycles;
+    let proving_time = pause_info.proving_time + halt_info.proving_time;
+    let timings = [pause_info.segment_timings(), halt_info.segment_timings()].concat();
+
+    let info = pause_info.merge(halt_info);
+    assert_eq!(info.stats.segments, segments);
+    assert_eq!(info.stats.total_cycles, total_cycles);
+    assert_eq!(info.stats.user_cycles, user_cycles);
+    assert_eq!(info.proving_time, proving_time);
+    assert_eq!(info.segment_timings(), timings);
+    assert_eq!(info.receipt.claim().unwrap().exit_code, ExitCode::Halted(0));
 }
 
 #[test]
@@ -476,6 +970,614 @@ fn continuation() {
     }
 }
 
+#[test]
+fn segment_exit_codes() {
+    let session = testutils::run_simple_loop_session();
+    let receipt = prove_session_fast(&session);
+    receipt.verify_integrity_with_context(&VerifierContext::default()).unwrap();
+    assert_eq!(
+        receipt.segment_exit_codes().unwrap(),
+        vec![ExitCode::SystemSplit, ExitCode::Halted(0)]
+    );
+}
+
+#[test]
+fn wall_clock_timeout() {
+    let session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+
+    let opts = ProverOpts::fast().with_wall_clock_timeout(Duration::from_nanos(1));
+    let prover = get_prover_server(&opts).unwrap();
+    let err = prover
+        .prove_session(&VerifierContext::default(), &session)
+        .err()
+        .unwrap();
+    let ProveError::TimedOut { segments_completed } = err.downcast::<ProveError>().unwrap() else {
+        panic!("expected a timeout");
+    };
+    assert_eq!(segments_completed.len(), 1);
+    assert_eq!(segments_completed[0].index, 0);
+    segments_completed[0]
+        .verify_integrity_with_context(&VerifierContext::default())
+        .unwrap();
+}
+
+#[test]
+fn max_total_cycles() {
+    let session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+
+    // Allow exactly the first segment.
+    let first_cycles = session.segments[0].resolve().unwrap().cycles();
+    let opts = ProverOpts::fast().with_max_total_cycles(first_cycles);
+    let err = get_prover_server(&opts)
+        .unwrap()
+        .prove_session(&VerifierContext::default(), &session)
+        .err()
+        .unwrap();
+    let ProveError::CycleLimitExceeded {
+        cycles_consumed,
+        segments_completed,
+    } = err.downcast::<ProveError>().unwrap()
+    else {
+        panic!("expected the cycle limit to be exceeded");
+    };
+    assert_eq!(cycles_consumed, first_cycles);
+    assert_eq!(segments_completed.len(), 1);
+    assert_eq!(segments_completed[0].index, 0);
+}
+
+#[test]
+fn prove_session_opts() {
+    let session = testutils::run_simple_loop_session();
+    let ctx = VerifierContext::default();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+
+    // The per-call options apply only to this call.
+    let opts = ProverOpts::fast().with_max_total_cycles(1);
+    let err = prover
+        .prove_session_opts(&ctx, &session, &opts)
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err.downcast::<ProveError>().unwrap(),
+        ProveError::CycleLimitExceeded { .. }
+    ));
+    prover.prove_session(&ctx, &session).unwrap();
+
+    let opts = ProverOpts::fast().with_hashfn("poseidon2".to_string());
+    assert!(prover.prove_session_opts(&ctx, &session, &opts).is_err());
+}
+
+/// A [SegmentRef] that always fails to resolve, to inject faults into a [Session].
+struct FailingSegmentRef;
+
+impl SegmentRef for FailingSegmentRef {
+    fn resolve(&self) -> Result<Segment> {
+        bail!("injected segment failure")
+    }
+}
+
+#[test]
+fn continue_on_segment_error() {
+    let mut session = testutils::run_simple_loop_session();
+    let good: Vec<_> = session
+        .segments
+        .iter()
+        .map(|x| SimpleSegmentRef::new(x.resolve().unwrap()))
+        .collect();
+    let [first, second]: [SimpleSegmentRef; 2] = good.try_into().ok().unwrap();
+    session.segments = vec![
+        Box::new(first),
+        Box::new(FailingSegmentRef),
+        Box::new(second),
+        Box::new(FailingSegmentRef),
+    ];
+
+    let ctx = VerifierContext::default();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    let err = prover.prove_session(&ctx, &session).err().unwrap();
+    assert!(err.downcast_ref::<ProveError>().is_none());
+
+    let opts = ProverOpts::fast().with_continue_on_segment_error(true);
+    let prover = get_prover_server(&opts).unwrap();
+    let err = prover.prove_session(&ctx, &session).err().unwrap();
+    let ProveError::SegmentsFailed { failures } = err.downcast::<ProveError>().unwrap() else {
+        panic!("expected segment failures");
+    };
+    let indices: Vec<u32> = failures.iter().map(|(index, _)| *index).collect();
+    assert_eq!(indices, vec![1, 3]);
+}
+
+#[test]
+fn repro_bundle() {
+    let session = testutils::run_simple_loop_session();
+
+    // Inject a failure by giving the prover no time to finish.
+    let opts = ProverOpts::fast().with_wall_clock_timeout(Duration::from_nanos(1));
+    let err = get_prover_server(&opts)
+        .unwrap()
+        .prove_session(&VerifierContext::default(), &session)
+        .err()
+        .unwrap();
+    assert!(err.downcast_ref::<ProveError>().is_some());
+
+    let mut bundle = Vec::new();
+    ReproBundle::new(&opts, &session)
+        .unwrap()
+        .write(&mut bundle)
+        .unwrap();
+    let err = ReproBundle::reproduce(bundle.as_slice()).err().unwrap();
+    assert!(matches!(
+        err.downcast_ref::<ProveError>(),
+        Some(ProveError::TimedOut { .. })
+    ));
+}
+
+#[test]
+fn segment_timings() {
+    let session = testutils::run_simple_loop_session();
+    let info = get_prover_server(&ProverOpts::fast())
+        .unwrap()
+        .prove_session(&VerifierContext::default(), &session)
+        .unwrap();
+
+    let timings = info.segment_timings();
+    assert_eq!(timings.len(), session.segments.len());
+    assert!(timings.iter().all(|duration| !duration.is_zero()));
+    assert!(timings.iter().sum::<Duration>() <= info.proving_time);
+}
+
+#[test]
+fn witness_peak_bytes() {
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    let mut po2_bytes = Vec::new();
+    for segment_limit_po2 in [14, 15] {
+        let program = testutil::simple_loop();
+        let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+        let env = ExecutorEnv::builder()
+            .segment_limit_po2(segment_limit_po2)
+            .build()
+            .unwrap();
+        let session = ExecutorImpl::new(env, image).unwrap().run().unwrap();
+        let info = prover
+            .prove_session(&VerifierContext::default(), &session)
+            .unwrap();
+        assert_eq!(info.segment_stats.len(), session.segments.len());
+        for (segment_ref, stats) in session.segments.iter().zip(&info.segment_stats) {
+            assert!(stats.witness_peak_bytes > 0);
+            po2_bytes.push((segment_ref.resolve().unwrap().po2(), stats.witness_peak_bytes));
+        }
+    }
+
+    // Larger segments need a larger witness.
+    po2_bytes.sort();
+    po2_bytes.dedup_by_key(|(po2, _)| *po2);
+    assert!(po2_bytes.len() >= 2);
+    for pair in po2_bytes.windows(2) {
+        assert!(pair[1].1 > pair[0].1);
+    }
+}
+
+#[test]
+fn effective_opts() {
+    let prover = get_prover_server(&ProverOpts::succinct()).unwrap();
+    let opts = prover.effective_opts();
+    assert_eq!(opts.receipt_kind, ReceiptKind::Succinct);
+    assert_eq!(opts.hashfn, "poseidon2");
+}
+
+#[test]
+fn lift_all() {
+    let session = testutils::run_simple_loop_session();
+
+    let ctx = VerifierContext::default();
+    let prover = get_prover_server(&ProverOpts::composite()).unwrap();
+    let lifted = prover.lift_all(&ctx, &session).unwrap();
+    assert_eq!(lifted.len(), 2);
+    for receipt in &lifted {
+        receipt.verify_integrity_with_context(&ctx).unwrap();
+    }
+
+    let joined = prover.join(&lifted[0], &lifted[1]).unwrap();
+    assert_eq!(joined.claim.digest(), session.claim().unwrap().digest());
+}
+
+#[test]
+fn prove_segments() {
+    let session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+
+    let ctx = VerifierContext::default();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    let mut segments = prover.prove_segments(&ctx, &session, 0..1).unwrap();
+    segments.extend(prover.prove_segments(&ctx, &session, 1..2).unwrap());
+    assert!(prover.prove_segments(&ctx, &session, 1..3).is_err());
+
+    let composite = CompositeReceipt {
+        segments,
+        assumptions: vec![],
+        journal_digest: session.journal.as_ref().map(|journal| journal.digest()),
+    };
+    composite.verify_integrity_with_context(&ctx).unwrap();
+
+    let expected = prove_session_fast(&session);
+    let expected = expected.inner.composite().unwrap();
+    assert_eq!(composite.segments.len(), expected.segments.len());
+    for (receipt, expected) in composite.segments.iter().zip(&expected.segments) {
+        assert_eq!(receipt.index, expected.index);
+        assert_eq!(receipt.claim.digest(), expected.claim.digest());
+    }
+    assert_eq!(
+        composite.claim().unwrap().digest(),
+        expected.claim().unwrap().digest()
+    );
+}
+
+#[test]
+fn prove_witness_bytes() {
+    let program = testutil::simple_loop();
+    let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+    let session = ExecutorImpl::new(ExecutorEnv::default(), image)
+        .unwrap()
+        .run()
+        .unwrap();
+    let segment = session.segments[0].resolve().unwrap();
+    let bytes = segment.to_witness_bytes().unwrap();
+
+    let ctx = VerifierContext::default();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    let expected = prover.prove_segment(&ctx, &segment).unwrap();
+    let receipt = prover.prove_witness_bytes(&ctx, &bytes).unwrap();
+    receipt.verify_integrity_with_context(&ctx).unwrap();
+    assert_eq!(receipt.index, expected.index);
+    assert_eq!(receipt.hashfn, expected.hashfn);
+    assert_eq!(receipt.claim, expected.claim);
+
+    let truncated = &bytes[..bytes.len() / 2];
+    assert!(prover.prove_witness_bytes(&ctx, truncated).is_err());
+}
+
+#[test]
+fn dev_mode_recursion() {
+    let session = testutils::run_simple_loop_session();
+    let receipt = prove_session_fast(&session);
+    let composite = receipt.inner.composite().unwrap();
+    assert!(composite.segments.len() > 1);
+
+    let prover = DevModeProver::new(ProverOpts::default());
+    let succinct = prover.compsite_to_succinct(composite).unwrap();
+    assert!(succinct.seal.is_empty());
+    assert_eq!(succinct.claim.digest(), session.claim().unwrap().digest());
+    let p254 = prover.identity_p254(&succinct).unwrap();
+    assert_eq!(p254.claim.digest(), succinct.claim.digest());
+
+    // Fake receipts only verify in dev mode.
+    assert!(!crate::is_dev_mode());
+    assert_eq!(
+        succinct.verify_integrity().unwrap_err(),
+        VerificationError::InvalidProof
+    );
+}
+
+#[test]
+fn dev_mode_prove_segment() {
+    let session = testutils::run_simple_loop_session();
+    let segments: Vec<_> = session
+        .segments
+        .iter()
+        .map(|x| x.resolve().unwrap())
+        .collect();
+    assert!(segments.len() > 1);
+
+    let ctx = VerifierContext::default();
+    let prover = DevModeProver::new(ProverOpts::default());
+    let receipts = segments
+        .iter()
+        .map(|segment| prover.prove_segment(&ctx, segment).unwrap())
+        .collect::<Vec<_>>();
+    for (idx, receipt) in receipts.iter().enumerate() {
+        assert_eq!(receipt.index, idx as u32);
+        assert!(receipt.seal.is_empty());
+        assert_eq!(receipt, &prover.prove_segment(&ctx, &segments[idx]).unwrap());
+    }
+
+    // The fake segment receipts reassemble into the session's claim.
+    let composite = CompositeReceipt {
+        segments: receipts,
+        assumptions: vec![],
+        journal_digest: session.journal.as_ref().map(|journal| journal.digest()),
+    };
+    assert_eq!(
+        composite.claim().unwrap().digest(),
+        session.claim().unwrap().digest()
+    );
+
+    // Fake receipts only verify in dev mode.
+    assert!(!crate::is_dev_mode());
+    assert_eq!(
+        composite.segments[0].verify_integrity_with_context(&ctx),
+        Err(VerificationError::InvalidProof)
+    );
+}
+
+#[test]
+fn verify_and_resolve() {
+    let halted = |exit_code, assumptions: Vec<ReceiptClaim>| ReceiptClaim {
+        pre: MaybePruned::Pruned(Digest::ZERO),
+        post: MaybePruned::Pruned(Digest::ZERO),
+        exit_code: ExitCode::Halted(exit_code),
+        input: Digest::ZERO,
+        output: MaybePruned::Value(Some(Output {
+            journal: MaybePruned::Pruned(Digest::ZERO),
+            assumptions: Assumptions(assumptions.into_iter().map(Into::into).collect()).into(),
+        })),
+    };
+    let first = SuccinctReceipt::fake(halted(1, vec![]));
+    let second = SuccinctReceipt::fake(halted(2, vec![]));
+    let conditional = SuccinctReceipt::fake(halted(
+        0,
+        vec![first.claim.clone(), second.claim.clone()],
+    ));
+
+    let prover = DevModeProver::new(ProverOpts::default());
+    let mut assumptions = HashMap::from([(first.claim.digest(), first)]);
+    let err = prover
+        .verify_and_resolve(&conditional, &assumptions)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains(&second.claim.digest().to_string()), "{err}");
+
+    assumptions.insert(second.claim.digest(), second);
+    let resolved = prover
+        .verify_and_resolve(&conditional, &assumptions)
+        .unwrap();
+    assert_eq!(resolved.claim.digest(), halted(0, vec![]).digest());
+}
+
+#[test]
+fn prove_session_channel() {
+    let session = testutils::run_simple_loop_session();
+
+    let (tx, rx) = mpsc::channel::<SegmentReceipt>();
+    let verifier = thread::spawn(move || {
+        let ctx = VerifierContext::default();
+        rx.iter()
+            .map(|receipt| {
+                receipt.verify_integrity_with_context(&ctx).unwrap();
+                receipt
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let ctx = VerifierContext::default();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    prover.prove_session_channel(&ctx, &session, tx).unwrap();
+    let segments = verifier.join().unwrap();
+    assert_eq!(segments.len(), session.segments.len());
+
+    let composite = CompositeReceipt {
+        segments,
+        assumptions: vec![],
+        journal_digest: session.journal.as_ref().map(|journal| journal.digest()),
+    };
+    composite.verify_integrity_with_context(&ctx).unwrap();
+
+    let expected = prove_session_fast(&session);
+    assert_eq!(
+        composite.claim().unwrap().digest(),
+        expected.claim().unwrap().digest()
+    );
+}
+
+#[test]
+fn segment_po2() {
+    let session = testutils::run_simple_loop_session();
+    assert!(session.segments.len() > 1);
+
+    let ctx = VerifierContext::default();
+    let prove = |po2| {
+        get_prover_server(&ProverOpts::fast().with_segment_po2(po2))
+            .unwrap()
+            .prove_session(&ctx, &session)
+    };
+
+    let receipt = prove(15).unwrap().receipt;
+    receipt.verify_integrity_with_context(&ctx).unwrap();
+    assert_eq!(
+        receipt.claim().unwrap().digest(),
+        session.claim().unwrap().digest()
+    );
+
+    let err = prove(13).err().unwrap().to_string();
+    assert!(err.contains("does not fit in segment_po2 13"), "{err}");
+    let err = prove(30).err().unwrap().to_string();
+    assert!(err.contains("outside the supported range"), "{err}");
+}
+
+#[test]
+fn prove_session_streaming() {
+    use crate::SessionEvents;
+
+    struct Counter(Rc<Cell<usize>>);
+
+    impl SessionEvents for Counter {
+        fn on_pre_prove_segment(&self, _: &Segment) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let mut session = testutils::run_simple_loop_session();
+    assert!(session.segments.len() > 1);
+    let proven = Rc::new(Cell::new(0));
+    session.add_hook(Counter(proven.clone()));
+
+    let ctx = VerifierContext::default();
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    let mut stream = prover.prove_session_streaming(&ctx, &session);
+    assert_eq!(proven.get(), 0);
+    let first = stream.next().unwrap().unwrap();
+    assert_eq!(proven.get(), 1);
+    first.verify_integrity_with_context(&ctx).unwrap();
+
+    let mut segments = vec![first];
+    segments.extend(stream.collect::<Result<Vec<_>>>().unwrap());
+    assert_eq!(proven.get(), session.segments.len());
+
+    let composite = CompositeReceipt {
+        segments,
+        assumptions: vec![],
+        journal_digest: session.journal.as_ref().map(|journal| journal.digest()),
+    };
+    composite.verify_integrity_with_context(&ctx).unwrap();
+    assert_eq!(
+        composite.claim().unwrap().digest(),
+        session.claim().unwrap().digest()
+    );
+}
+
+/// A [ProverServer] that counts lifts and fails the first `failing_joins` joins.
+struct FlakyJoinProver {
+    inner: Rc<dyn ProverServer>,
+    lifts: Cell<usize>,
+    failing_joins: Cell<usize>,
+}
+
+impl ProverServer for FlakyJoinProver {
+    fn prove_session(&self, ctx: &VerifierContext, session: &Session) -> Result<ProveInfo> {
+        self.inner.prove_session(ctx, session)
+    }
+
+    fn prove_segment(&self, ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt> {
+        self.inner.prove_segment(ctx, segment)
+    }
+
+    fn lift(&self, receipt: &SegmentReceipt) -> Result<SuccinctReceipt> {
+        self.lifts.set(self.lifts.get() + 1);
+        self.inner.lift(receipt)
+    }
+
+    fn join(&self, a: &SuccinctReceipt, b: &SuccinctReceipt) -> Result<SuccinctReceipt> {
+        if self.failing_joins.get() > 0 {
+            self.failing_joins.set(self.failing_joins.get() - 1);
+            anyhow::bail!("simulated join failure");
+        }
+        self.inner.join(a, b)
+    }
+
+    fn resolve(
+        &self,
+        conditional: &SuccinctReceipt,
+        assumption: &SuccinctReceipt,
+    ) -> Result<SuccinctReceipt> {
+        self.inner.resolve(conditional, assumption)
+    }
+
+    fn identity_p254(&self, a: &SuccinctReceipt) -> Result<SuccinctReceipt> {
+        self.inner.identity_p254(a)
+    }
+}
+
+#[test]
+fn compress_resumable() {
+    let session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+
+    let prover = FlakyJoinProver {
+        inner: get_prover_server(&ProverOpts::composite()).unwrap(),
+        lifts: Cell::new(0),
+        failing_joins: Cell::new(1),
+    };
+    let receipt = prover
+        .prove_session(&VerifierContext::default(), &session)
+        .unwrap()
+        .receipt;
+    let composite = receipt.inner.composite().unwrap();
+
+    let mut state = CompressState::default();
+    prover
+        .composite_to_succinct_resumable(composite, &mut state)
+        .unwrap_err();
+    assert_eq!(state.segments_done(), 1);
+    assert_eq!(prover.lifts.get(), 2);
+
+    // Resuming does not lift the segments again.
+    let succinct = prover
+        .composite_to_succinct_resumable(composite, &mut state)
+        .unwrap();
+    assert_eq!(state.segments_done(), 2);
+    assert_eq!(prover.lifts.get(), 2);
+    succinct.verify_integrity().unwrap();
+    assert_eq!(succinct.claim.digest(), session.claim().unwrap().digest());
+}
+
+#[test]
+fn lift_cache() {
+    let session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+
+    let prover = FlakyJoinProver {
+        inner: get_prover_server(&ProverOpts::composite()).unwrap(),
+        lifts: Cell::new(0),
+        failing_joins: Cell::new(0),
+    };
+    let receipt = prover
+        .prove_session(&VerifierContext::default(), &session)
+        .unwrap()
+        .receipt;
+
+    let cache = Arc::new(LiftCache::new());
+    let opts = ProverOpts::succinct().with_lift_cache(cache.clone());
+    let first = prover.compress(&opts, &receipt).unwrap();
+    assert_eq!(prover.lifts.get(), 2);
+    assert_eq!(cache.len(), 2);
+
+    // Compressing again reuses the cached lifts.
+    let second = prover
+        .compress(&opts.with_retain_claim_tree(true), &receipt)
+        .unwrap();
+    assert_eq!(prover.lifts.get(), 2);
+    second
+        .verify_integrity_with_context(&VerifierContext::default())
+        .unwrap();
+    assert_eq!(
+        first.claim().unwrap().digest(),
+        second.claim().unwrap().digest()
+    );
+}
+
+#[test]
+fn claim_tree() {
+    let session = testutils::run_simple_loop_session();
+    assert_eq!(session.segments.len(), 2);
+
+    let prover = get_prover_server(&ProverOpts::composite()).unwrap();
+    let composite = prover
+        .prove_session(&VerifierContext::default(), &session)
+        .unwrap()
+        .receipt;
+
+    let opts = ProverOpts::succinct().with_retain_claim_tree(true);
+    let receipt = prover.compress(&opts, &composite).unwrap();
+    let succinct = receipt.inner.succinct().unwrap();
+    let tree = succinct.claim_tree().unwrap();
+    assert_eq!(tree.leaves().len(), 2);
+    assert_eq!(tree.claim(), &succinct.claim);
+
+    // The tree is dropped unless requested.
+    let receipt = prover.compress(&ProverOpts::succinct(), &composite).unwrap();
+    assert!(receipt.inner.succinct().unwrap().claim_tree().is_none());
+
+    // Proving a session straight to a succinct receipt honors the option too.
+    let receipt = get_prover_server(&opts)
+        .unwrap()
+        .prove_session(&VerifierContext::default(), &session)
+        .unwrap()
+        .receipt;
+    let tree = receipt.inner.succinct().unwrap().claim_tree().unwrap();
+    assert_eq!(tree.leaves().len(), 2);
+}
+
 #[test]
 fn sys_input() {
     use hex::FromHex;
@@ -499,7 +1601,7 @@ fn sys_input() {
 mod docker {
     use crate::{
         get_prover_server,
-        host::server::prove::{DevModeProver, ProverServer},
+        host::server::prove::{compression_level, DevModeProver, ProverServer},
         ExecutorEnv, InnerReceipt, ProverOpts, Receipt, ReceiptKind,
     };
     use risc0_zkvm_methods::{multi_test::MultiTestSpec, MULTI_TEST_ELF, MULTI_TEST_ID};
@@ -511,7 +1613,7 @@ mod docker {
             .unwrap()
             .build()
             .unwrap();
-        let opts = ProverOpts::compact();
+        let opts = ProverOpts::groth16();
         get_prover_server(&opts)
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
@@ -525,29 +1627,35 @@ mod docker {
     fn test_compress(opts: ProverOpts, receipt: &Receipt) {
         let prover = get_prover_server(&opts).unwrap();
         let receipt = prover.compress(&opts, receipt).unwrap();
-        match opts.receipt_kind {
-            ReceiptKind::Composite => match receipt.inner {
-                InnerReceipt::Composite(_)
-                | InnerReceipt::Succinct(_)
-                | InnerReceipt::Compact(_) => {}
-                InnerReceipt::Fake { .. } => panic!("unexpected fake receipt"),
-            },
-            ReceiptKind::Succinct => match receipt.inner {
-                InnerReceipt::Succinct(_) | InnerReceipt::Compact(_) => {}
-                InnerReceipt::Composite(_) => panic!("expected receipt to be succinct or smaller"),
-                InnerReceipt::Fake { .. } => panic!("unexpected fake receipt"),
-            },
-            ReceiptKind::Compact => match receipt.inner {
-                InnerReceipt::Compact(_) => {}
-                InnerReceipt::Succinct(_) | InnerReceipt::Composite(_) => {
-                    panic!("expected receipt to be compact or smaller")
-                }
-                InnerReceipt::Fake { .. } => panic!("unexpected fake receipt"),
-            },
-        };
+        assert!(
+            !matches!(receipt.inner, InnerReceipt::Fake { .. }),
+            "unexpected fake receipt"
+        );
+        assert!(
+            compression_level(receipt.kind()) >= compression_level(opts.receipt_kind),
+            "expected a {:?} receipt or smaller, got {:?}",
+            opts.receipt_kind,
+            receipt.kind()
+        );
         receipt.verify(MULTI_TEST_ID).unwrap();
     }
 
+    #[test]
+    fn convert_receipt_kind_compact() {
+        let prover = get_prover_server(&ProverOpts::groth16()).unwrap();
+        let composite = generate_receipt(ProverOpts::composite());
+
+        let compact = composite
+            .convert_to(ReceiptKind::Groth16, prover.as_ref())
+            .unwrap();
+        assert_eq!(compact.kind(), ReceiptKind::Groth16);
+        compact.verify(MULTI_TEST_ID).unwrap();
+
+        for kind in [ReceiptKind::Composite, ReceiptKind::Succinct] {
+            assert!(compact.convert_to(kind, prover.as_ref()).is_err());
+        }
+    }
+
     fn test_fake_compress(receipt: &Receipt) {
         fn ensure_fake(receipt: Receipt) {
             let InnerReceipt::Fake { claim: _ } = receipt.inner else {
@@ -560,13 +1668,14 @@ mod docker {
             },
             receipt.clone().journal.bytes,
         );
+        assert_eq!(fake.kind(), ReceiptKind::Composite);
 
-        let prover = DevModeProver;
+        let prover = DevModeProver::new(ProverOpts::default());
         let receipt = prover.compress(&ProverOpts::composite(), &fake).unwrap();
         ensure_fake(receipt);
         let receipt = prover.compress(&ProverOpts::succinct(), &fake).unwrap();
         ensure_fake(receipt);
-        let receipt = prover.compress(&ProverOpts::compact(), &fake).unwrap();
+        let receipt = prover.compress(&ProverOpts::groth16(), &fake).unwrap();
         ensure_fake(receipt);
     }
 
@@ -586,19 +1695,19 @@ mod docker {
         let composite_receipt = &generate_receipt(ProverOpts::composite());
         self::test_compress(ProverOpts::composite(), composite_receipt);
         self::test_compress(ProverOpts::succinct(), composite_receipt);
-        self::test_compress(ProverOpts::compact(), composite_receipt);
+        self::test_compress(ProverOpts::groth16(), composite_receipt);
 
         // succinct receipts
         let succinct_receipt = &generate_receipt(ProverOpts::succinct());
         self::test_compress(ProverOpts::composite(), succinct_receipt);
         self::test_compress(ProverOpts::succinct(), succinct_receipt);
-        self::test_compress(ProverOpts::compact(), succinct_receipt);
+        self::test_compress(ProverOpts::groth16(), succinct_receipt);
 
         // compact receipts
-        let compact_receipt = &generate_receipt(ProverOpts::compact());
+        let compact_receipt = &generate_receipt(ProverOpts::groth16());
         self::test_compress(ProverOpts::composite(), compact_receipt);
         self::test_compress(ProverOpts::succinct(), compact_receipt);
-        self::test_compress(ProverOpts::compact(), compact_receipt);
+        self::test_compress(ProverOpts::groth16(), compact_receipt);
 
         // fake receipts
         self::test_fake_compress(compact_receipt);
@@ -606,22 +1715,24 @@ mod docker {
 }
 
 mod sys_verify {
-    use std::sync::OnceLock;
+    use std::{cell::Cell, sync::OnceLock};
 
     use crate::ReceiptKind;
+    use risc0_zkp::verify::VerificationError;
     use risc0_zkvm_methods::{
         multi_test::MultiTestSpec, HELLO_COMMIT_ELF, HELLO_COMMIT_ID, MULTI_TEST_ELF, MULTI_TEST_ID,
     };
     use test_log::test;
 
-    use super::{get_prover_server, prover_opts_fast};
+    use super::get_prover_server;
     use crate::{
-        serde::to_vec, sha::Digestible, ExecutorEnv, ExecutorEnvBuilder, ExitCode, ProverOpts,
-        Receipt,
+        serde::to_vec,
+        sha::{Digest, Digestible},
+        ExecutorEnv, ExecutorEnvBuilder, ExecutorImpl, ExitCode, ProverOpts, Receipt,
     };
 
     fn prove_hello_commit() -> Receipt {
-        get_prover_server(&prover_opts_fast())
+        get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(ExecutorEnv::default(), HELLO_COMMIT_ELF)
             .unwrap()
@@ -633,6 +1744,13 @@ mod sys_verify {
             hashfn: "sha-256".to_string(),
             prove_guest_errors: true,
             receipt_kind: ReceiptKind::Composite,
+            retain_claim_tree: false,
+            wall_clock_timeout: None,
+            stop_after: None,
+            continue_on_segment_error: false,
+            segment_po2: None,
+            max_total_cycles: None,
+            lift_cache: None,
         };
 
         let env = ExecutorEnvBuilder::default()
@@ -676,7 +1794,7 @@ mod sys_verify {
             .add_assumption(hello_commit_receipt().clone())
             .build()
             .unwrap();
-        get_prover_server(&prover_opts_fast())
+        get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
             .unwrap()
@@ -685,6 +1803,74 @@ mod sys_verify {
             .unwrap();
     }
 
+    #[test]
+    fn add_assumption_lazy() {
+        // The receipt is never produced if the guest does not verify it.
+        let env = ExecutorEnv::builder()
+            .write(&MultiTestSpec::DoNothing)
+            .unwrap()
+            .add_assumption_lazy(|| panic!("unused assumption was produced"))
+            .build()
+            .unwrap();
+        ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let spec = MultiTestSpec::SysVerify(vec![(
+            HELLO_COMMIT_ID.into(),
+            hello_commit_receipt().journal.bytes.clone(),
+        )]);
+        // Lazy receipts are produced in order until one matches, and the closures may borrow
+        // from the caller.
+        let produced = Cell::new(0);
+        let env = ExecutorEnv::builder()
+            .write(&spec)
+            .unwrap()
+            .add_assumption_lazy(|| {
+                produced.set(produced.get() + 1);
+                Ok(hello_commit_receipt().clone())
+            })
+            .add_assumption_lazy(|| panic!("assumption after the match was produced"))
+            .build()
+            .unwrap();
+        get_prover_server(&ProverOpts::fast())
+            .unwrap()
+            .prove(env, MULTI_TEST_ELF)
+            .unwrap()
+            .receipt
+            .verify(MULTI_TEST_ID)
+            .unwrap();
+        assert_eq!(produced.get(), 1);
+    }
+
+    #[test]
+    fn verify_with_expected_assumption() {
+        let spec = MultiTestSpec::SysVerify(vec![(
+            HELLO_COMMIT_ID.into(),
+            hello_commit_receipt().journal.bytes.clone(),
+        )]);
+        let env = ExecutorEnv::builder()
+            .write(&spec)
+            .unwrap()
+            .add_assumption(hello_commit_receipt().clone())
+            .build()
+            .unwrap();
+        let receipt = get_prover_server(&ProverOpts::fast())
+            .unwrap()
+            .prove(env, MULTI_TEST_ELF)
+            .unwrap()
+            .receipt;
+
+        let assumption_claim = hello_commit_receipt().claim().unwrap().digest();
+        receipt
+            .verify_with_expected_assumption(MULTI_TEST_ID, assumption_claim)
+            .unwrap();
+        assert!(receipt
+            .verify_with_expected_assumption(MULTI_TEST_ID, Digest::ZERO)
+            .is_err());
+    }
+
     #[test]
     fn sys_verify_2() {
         let spec = MultiTestSpec::SysVerify(vec![(
@@ -699,7 +1885,7 @@ mod sys_verify {
             .unwrap()
             .build()
             .unwrap();
-        assert!(get_prover_server(&prover_opts_fast())
+        assert!(get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
             .is_err());
@@ -722,7 +1908,7 @@ mod sys_verify {
             .unwrap();
 
         // TODO(#982) Conditional receipts currently return an error on verification.
-        assert!(get_prover_server(&prover_opts_fast())
+        assert!(get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
             .is_err());
@@ -748,7 +1934,7 @@ mod sys_verify {
             .add_assumption(hello_commit_receipt().clone())
             .build()
             .unwrap();
-        get_prover_server(&prover_opts_fast())
+        get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
             .unwrap()
@@ -763,7 +1949,7 @@ mod sys_verify {
             .unwrap()
             .build()
             .unwrap();
-        assert!(get_prover_server(&prover_opts_fast())
+        assert!(get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
             .is_err());
@@ -777,12 +1963,53 @@ mod sys_verify {
             .build()
             .unwrap();
         // TODO(#982) Conditional receipts currently return an error on verification.
-        assert!(get_prover_server(&prover_opts_fast())
+        assert!(get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
             .is_err());
     }
 
+    #[test]
+    fn add_assumption_by_digest() {
+        let claim = hello_commit_receipt().claim().unwrap();
+        let claim_digest = claim.digest();
+        let spec = &MultiTestSpec::SysVerifyIntegrity {
+            claim_words: to_vec(&claim).unwrap(),
+        };
+
+        let fetches = Cell::new(0);
+        let fetcher = |digest: Digest| {
+            assert_eq!(digest, claim_digest);
+            fetches.set(fetches.get() + 1);
+            Ok(hello_commit_receipt().clone())
+        };
+        let env = || {
+            ExecutorEnv::builder()
+                .write(&spec)
+                .unwrap()
+                .add_assumption_by_digest(claim_digest, fetcher)
+                .build()
+                .unwrap()
+        };
+
+        // Execution does not need the receipt.
+        ExecutorImpl::from_elf(env(), MULTI_TEST_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(fetches.get(), 0);
+
+        // Proving fetches it once, resulting in an unconditional receipt.
+        get_prover_server(&ProverOpts::fast())
+            .unwrap()
+            .prove(env(), MULTI_TEST_ELF)
+            .unwrap()
+            .receipt
+            .verify(MULTI_TEST_ID)
+            .unwrap();
+        assert_eq!(fetches.get(), 1);
+    }
+
     #[test]
     fn sys_verify_integrity_halt_1() {
         // Generate a receipt for a execution ending in a guest error indicated by
@@ -800,7 +2027,7 @@ mod sys_verify {
             .add_assumption(halt_receipt)
             .build()
             .unwrap();
-        get_prover_server(&prover_opts_fast())
+        get_prover_server(&ProverOpts::fast())
             .unwrap()
             .prove(env, MULTI_TEST_ELF)
             .unwrap()
@@ -808,6 +2035,15 @@ mod sys_verify {
             .verify(MULTI_TEST_ID)
             .unwrap();
     }
+
+    #[test]
+    fn verify_guest_error() {
+        assert_eq!(prove_halt(1).verify_guest_error(MULTI_TEST_ID).unwrap(), 1);
+        assert_eq!(
+            prove_halt(0).verify_guest_error(MULTI_TEST_ID).unwrap_err(),
+            VerificationError::UnexpectedExitCode
+        );
+    }
 }
 
 mod soundness {
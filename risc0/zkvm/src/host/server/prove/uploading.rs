@@ -0,0 +1,185 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use anyhow::{bail, ensure, Context, Result};
+use risc0_zkvm_platform::fileno;
+
+use crate::{
+    compute_image_id,
+    host::{
+        prove_info::ProveInfo,
+        receipt::{SegmentReceipt, SuccinctReceipt},
+    },
+    ExecutorEnv, ProverOpts, ProverServer, Receipt, Segment, Session, VerifierContext,
+};
+
+/// The state of a proving job submitted through a [RemoteClient].
+pub enum RemoteStatus {
+    /// The job is still being proven.
+    Running,
+
+    /// The job finished and produced the given [ProveInfo].
+    Succeeded(ProveInfo),
+
+    /// The job failed with the given error message.
+    Failed(String),
+}
+
+/// A connection to a remote proving service, as used by [UploadingProver].
+pub trait RemoteClient {
+    /// Submit an ELF binary and its input, returning an identifier for the job.
+    fn submit(&self, elf: &[u8], input: &[u8]) -> Result<String>;
+
+    /// Query the status of a job previously returned by [RemoteClient::submit].
+    fn status(&self, job_id: &str) -> Result<RemoteStatus>;
+}
+
+/// An implementation of a [ProverServer] that uploads the execution inputs to a
+/// remote proving service and polls it for the resulting [Receipt].
+///
+/// Receipts returned by the remote service are verified locally against the
+/// image ID of the submitted ELF before being returned.
+///
+/// Only ELF binaries can be proven with this prover; the lower level methods of
+/// [ProverServer] such as [ProverServer::prove_segment] are unsupported.
+///
+/// Only the ELF and the input of the [ExecutorEnv] are sent to the remote
+/// service. Proving returns an error if the environment sets anything else that
+/// affects execution, such as environment variables, a segment limit,
+/// assumptions, I/O callbacks or additional input channels. Callbacks that only
+/// observe execution, such as trace callbacks, are not called.
+pub struct UploadingProver<C: RemoteClient> {
+    client: C,
+    poll_interval: Duration,
+}
+
+impl<C: RemoteClient> UploadingProver<C> {
+    /// Construct an [UploadingProver] that submits jobs through the given
+    /// [RemoteClient].
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Set the interval between status requests to the remote service.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+}
+
+impl<C: RemoteClient> ProverServer for UploadingProver<C> {
+    fn prove_with_ctx(
+        &self,
+        env: ExecutorEnv<'_>,
+        ctx: &VerifierContext,
+        elf: &[u8],
+    ) -> Result<ProveInfo> {
+        check_env(&env)?;
+        let image_id = compute_image_id(elf)?;
+        let job_id = self.client.submit(elf, &env.input)?;
+        tracing::debug!("remote proving job: {job_id}");
+
+        loop {
+            match self.client.status(&job_id)? {
+                RemoteStatus::Running => std::thread::sleep(self.poll_interval),
+                RemoteStatus::Succeeded(info) => {
+                    info.receipt
+                        .verify_with_context(ctx, image_id)
+                        .with_context(|| {
+                            format!("failed to verify receipt returned for job {job_id}")
+                        })?;
+                    return Ok(info);
+                }
+                RemoteStatus::Failed(err) => bail!("remote proving job {job_id} failed: {err}"),
+            }
+        }
+    }
+
+    fn prove_session(&self, _ctx: &VerifierContext, _session: &Session) -> Result<ProveInfo> {
+        bail!("UploadingProver can only prove ELF binaries, not local sessions")
+    }
+
+    fn prove_segment(&self, _ctx: &VerifierContext, _segment: &Segment) -> Result<SegmentReceipt> {
+        bail!("This is unsupported for the uploading prover.")
+    }
+
+    fn lift(&self, _receipt: &SegmentReceipt) -> Result<SuccinctReceipt> {
+        bail!("This is unsupported for the uploading prover.")
+    }
+
+    fn join(&self, _a: &SuccinctReceipt, _b: &SuccinctReceipt) -> Result<SuccinctReceipt> {
+        bail!("This is unsupported for the uploading prover.")
+    }
+
+    fn resolve(
+        &self,
+        _conditional: &SuccinctReceipt,
+        _assumption: &SuccinctReceipt,
+    ) -> Result<SuccinctReceipt> {
+        bail!("This is unsupported for the uploading prover.")
+    }
+
+    fn identity_p254(&self, _a: &SuccinctReceipt) -> Result<SuccinctReceipt> {
+        bail!("This is unsupported for the uploading prover.")
+    }
+
+    fn compress(&self, _opts: &ProverOpts, _receipt: &Receipt) -> Result<Receipt> {
+        bail!("This is unsupported for the uploading prover.")
+    }
+}
+
+/// Return an error naming the settings of `env` that cannot be sent to the remote service.
+fn check_env(env: &ExecutorEnv<'_>) -> Result<()> {
+    let assumptions = env.assumptions.borrow();
+    let settings = [
+        ("env_vars", !env.env_vars.is_empty()),
+        ("args", !env.args.is_empty()),
+        ("segment_limit_po2", env.segment_limit_po2.is_some()),
+        ("session_limit", env.session_limit.is_some()),
+        (
+            "assumptions",
+            !assumptions.cached.is_empty()
+                || !assumptions.pending.is_empty()
+                || !assumptions.deferred.is_empty(),
+        ),
+        ("io callbacks", !env.slice_io.borrow().inner.is_empty()),
+        (
+            "input channels",
+            env.posix_io
+                .borrow()
+                .read_fds
+                .keys()
+                .any(|&fd| fd != fileno::STDIN),
+        ),
+        ("input_digest", env.input_digest.is_some()),
+        ("break_on_write", env.break_on_write.is_some()),
+        ("fixed_segment_cycles", env.fixed_segment_cycles.is_some()),
+    ];
+    let unsupported: Vec<_> = settings
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect();
+    ensure!(
+        unsupported.is_empty(),
+        "UploadingProver only sends the ELF and input to the remote service, but the environment \
+         sets: {}",
+        unsupported.join(", ")
+    );
+    Ok(())
+}
@@ -15,17 +15,23 @@
 //! This module defines [Session] and [Segment] which provides a way to share
 //! execution traces between the execution phase and the proving phase.
 
-use std::{collections::BTreeSet, fs, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write as _,
+    fs,
+    ops::ControlFlow,
+    path::PathBuf,
+};
 
 use anyhow::{ensure, Result};
 use risc0_binfmt::{MemoryImage, SystemState};
-use risc0_circuit_rv32im::prove::segment::Segment as CircuitSegment;
+use risc0_circuit_rv32im::prove::{emu::rv32im::InsnKind, segment::Segment as CircuitSegment};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     host::{client::env::SegmentPath, prove_info::SessionStats},
-    sha::Digest,
-    Assumption, Assumptions, ExitCode, Journal, Output, ReceiptClaim,
+    sha::{Digest, Digestible},
+    Assumption, Assumptions, ExitCode, Journal, Output, Receipt, ReceiptClaim,
 };
 
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
@@ -78,6 +84,20 @@ pub struct Session {
 
     /// The system state of the final [MemoryImage] at the end of execution.
     pub post_state: SystemState,
+
+    /// True if execution was stopped by the breakpoint set with
+    /// [crate::ExecutorEnvBuilder::break_on_write].
+    pub breakpoint: bool,
+
+    /// Diagnostic events emitted by the guest with `env::emit_event`, in
+    /// order. These are not part of the journal and are not covered by the
+    /// proof.
+    pub events: Vec<Vec<u8>>,
+
+    pub(crate) pc_stats: Option<HashMap<u32, u64>>,
+
+    // Receipts for assumptions added by claim digest, fetched by the local prover.
+    pub(crate) deferred_assumptions: HashMap<Digest, Receipt>,
 }
 
 /// The execution trace of a portion of a program.
@@ -104,6 +124,48 @@ impl Segment {
     pub fn po2(&self) -> usize {
         self.inner.po2
     }
+
+    /// Give the number of cycles in this [Segment]'s execution trace, `2^po2`.
+    ///
+    /// This includes the padding after the guest's own instructions, as it is the size of the
+    /// trace that determines the cost of proving the segment.
+    pub fn cycles(&self) -> u64 {
+        1 << self.inner.po2
+    }
+
+    /// Serialize this [Segment], so that it can be proven in another process with
+    /// [ProverServer::prove_witness_bytes](crate::ProverServer::prove_witness_bytes).
+    ///
+    /// This allows the executor and the prover to run on separate machines.
+    pub fn to_witness_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserialize a [Segment] produced by [Segment::to_witness_bytes].
+    pub fn from_witness_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Count how many times each instruction kind was executed in this
+    /// [Segment].
+    ///
+    /// The segment does not retain its instruction trace, so this replays the
+    /// segment from its partial memory image. The counts are deterministic for
+    /// a given [Segment].
+    pub fn instruction_histogram(&self) -> Result<HashMap<InsnKind, u64>> {
+        self.inner.instruction_histogram()
+    }
+
+    /// The [ReceiptClaim] that a receipt for this [Segment] will attest to.
+    pub(crate) fn claim(&self) -> ReceiptClaim {
+        ReceiptClaim {
+            pre: self.inner.pre_state.clone().into(),
+            post: self.inner.post_state.clone().into(),
+            exit_code: self.inner.exit_code,
+            input: self.inner.input_digest,
+            output: self.output.clone().into(),
+        }
+    }
 }
 
 /// A reference to a [Segment].
@@ -118,6 +180,17 @@ pub trait SegmentRef: Send {
 
 /// The Events of [Session]
 pub trait SessionEvents {
+    /// Checked before the proving of each segment, ahead of
+    /// [SessionEvents::on_pre_prove_segment].
+    ///
+    /// Returning [ControlFlow::Break] cancels proving, which then fails with
+    /// [crate::ProveError::Cancelled]. This lets a hook stop a long proving job
+    /// cleanly, e.g. on a shutdown signal.
+    #[allow(unused)]
+    fn check_prove_segment(&self, segment: &Segment) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
     /// Fired before the proving of a segment starts.
     #[allow(unused)]
     fn on_pre_prove_segment(&self, segment: &Segment) {}
@@ -154,6 +227,10 @@ impl Session {
             total_cycles,
             pre_state,
             post_state,
+            breakpoint: false,
+            events: Vec::new(),
+            pc_stats: None,
+            deferred_assumptions: HashMap::new(),
         }
     }
 
@@ -180,6 +257,12 @@ impl Session {
                                 .iter()
                                 .filter_map(|a| match a {
                                     Assumption::Proven(_) => None,
+                                    // Fetched deferred assumptions are resolved by the prover.
+                                    Assumption::Unresolved(r)
+                                        if self.deferred_assumptions.contains_key(&r.digest()) =>
+                                    {
+                                        None
+                                    }
                                     Assumption::Unresolved(r) => Some(r.clone()),
                                 })
                                 .collect::<Vec<_>>(),
@@ -223,6 +306,32 @@ impl Session {
         tracing::debug!("cycle efficiency: {}%", cycle_efficiency as u32);
     }
 
+    /// Returns a human-readable summary of the session, one field per line, for use in logs.
+    ///
+    /// Paging cycles are not tracked separately; they are included in the overhead cycles, along
+    /// with continuation overhead and po2 padding.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "exit code: {:?}", self.exit_code).unwrap();
+        writeln!(out, "segments: {}", self.segments.len()).unwrap();
+        writeln!(out, "total cycles: {}", self.total_cycles).unwrap();
+        writeln!(out, "user cycles: {}", self.user_cycles).unwrap();
+        writeln!(
+            out,
+            "overhead cycles: {}",
+            self.total_cycles.saturating_sub(self.user_cycles)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "journal bytes: {}",
+            self.journal.as_ref().map_or(0, |journal| journal.bytes.len())
+        )
+        .unwrap();
+        write!(out, "assumptions: {}", self.assumptions.len()).unwrap();
+        out
+    }
+
     /// Returns stats for the session
     ///
     /// This contains cycle and segment information about the session useful for debugging and measuring performance.
@@ -233,6 +342,105 @@ impl Session {
             user_cycles: self.user_cycles,
         }
     }
+
+    /// Returns the user cycles spent at each instruction address, if the session was executed
+    /// with [ExecutorEnvBuilder::enable_profiling](crate::ExecutorEnvBuilder::enable_profiling).
+    ///
+    /// The cycles of an instruction include any paging and syscall work it triggered, so the
+    /// values add up to [Session::user_cycles].
+    pub fn pc_stats(&self) -> Option<&HashMap<u32, u64>> {
+        self.pc_stats.as_ref()
+    }
+
+    /// Returns a lightweight [SessionManifest] describing this session, for indexing.
+    ///
+    /// Each segment is resolved to read its po2, but none of the segment data is retained, so the
+    /// manifest cannot be used to prove the session.
+    pub fn to_manifest(&self) -> Result<SessionManifest> {
+        let segment_po2s = self
+            .segments
+            .iter()
+            .map(|segment_ref| Ok(segment_ref.resolve()?.po2() as u32))
+            .collect::<Result<_>>()?;
+        Ok(SessionManifest {
+            exit_code: self.exit_code,
+            segment_po2s,
+            journal: self.journal.as_ref().map(|journal| journal.bytes.clone()),
+            user_cycles: self.user_cycles,
+            total_cycles: self.total_cycles,
+        })
+    }
+
+    /// Returns the recursion work needed to compress a proof of this session into a single
+    /// [SuccinctReceipt](crate::SuccinctReceipt).
+    ///
+    /// This mirrors [ProverServer::compress](crate::ProverServer::compress): every segment is
+    /// lifted, the lifted receipts are joined in order, and every assumption is then resolved.
+    pub fn recursion_plan(&self) -> RecursionPlan {
+        let lifts = self.segments.len();
+        let joins = lifts.saturating_sub(1);
+        RecursionPlan {
+            lifts,
+            joins,
+            join_depth: joins,
+            resolves: self.assumptions.len(),
+        }
+    }
+}
+
+/// A compact, serializable summary of a [Session], without any segment data.
+///
+/// See [Session::to_manifest].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionManifest {
+    /// The [ExitCode] of the session.
+    pub exit_code: ExitCode,
+
+    /// The po2 of each segment, in order.
+    pub segment_po2s: Vec<u32>,
+
+    /// The data publicly committed by the guest program, if any.
+    pub journal: Option<Vec<u8>>,
+
+    /// The number of user cycles without any overhead for continuations or po2 padding.
+    pub user_cycles: u64,
+
+    /// Total number of cycles, including continuation overhead and po2 padding.
+    pub total_cycles: u64,
+}
+
+impl SessionManifest {
+    /// The number of segments in the session.
+    pub fn segment_count(&self) -> usize {
+        self.segment_po2s.len()
+    }
+}
+
+/// The recursion proofs required to compress a [Session] into a succinct receipt.
+///
+/// See [Session::recursion_plan].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecursionPlan {
+    /// Number of `lift` proofs, one per segment.
+    pub lifts: usize,
+
+    /// Number of `join` proofs.
+    pub joins: usize,
+
+    /// Depth of the join tree, i.e. the number of joins on the longest path from a lifted
+    /// segment to the root. Joins are applied sequentially, so this is the number of joins that
+    /// cannot be run in parallel.
+    pub join_depth: usize,
+
+    /// Number of `resolve` proofs, one per assumption.
+    pub resolves: usize,
+}
+
+impl RecursionPlan {
+    /// Total number of recursion proofs required.
+    pub fn total_proofs(&self) -> usize {
+        self.lifts + self.joins + self.resolves
+    }
 }
 
 /// Implementation of a [SegmentRef] that does not save the segment.
@@ -17,7 +17,13 @@ use core::mem;
 use crypto_bigint::{
     rand_core::CryptoRngCore, CheckedMul, Encoding, NonZero, Random, RandomMod, U256, U512,
 };
-use risc0_zkvm_platform::syscall::bigint;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use risc0_binfmt::MemoryImage;
+use risc0_circuit_rv32im::prove::emu::testutil;
+use risc0_zkvm_platform::{syscall::bigint, PAGE_SIZE};
+
+use crate::{ExecutorEnv, ExecutorImpl, Session};
 
 // Convert to little-endian u32 array. Only reinterpretation on LE machines.
 fn bigint_to_arr(num: &U256) -> [u32; bigint::WIDTH_WORDS] {
@@ -36,7 +42,7 @@ fn arr_to_bigint(mut arr: [u32; bigint::WIDTH_WORDS]) -> U256 {
     U256::from_le_bytes(bytemuck::cast(arr))
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct BigIntTestCase {
     pub x: [u32; bigint::WIDTH_WORDS],
     pub y: [u32; bigint::WIDTH_WORDS],
@@ -126,3 +132,23 @@ pub fn generate_bigint_test_cases(
     cases.extend((0..rand_count).map(|_| BigIntTestCase::sample(rng)));
     cases
 }
+
+/// Generate the same test cases as [generate_bigint_test_cases], with the
+/// random cases drawn from an RNG seeded with `seed`, so that a failing case
+/// can be reproduced exactly. [ChaCha8Rng] is used because its output, unlike
+/// that of `StdRng`, does not change between rand versions.
+pub fn generate_bigint_test_cases_seeded(seed: u64, rand_count: usize) -> Vec<BigIntTestCase> {
+    generate_bigint_test_cases(&mut ChaCha8Rng::seed_from_u64(seed), rand_count)
+}
+
+/// Execute the `simple_loop` test program with a 16k cycle segment limit, which splits it into two
+/// segments.
+pub fn run_simple_loop_session() -> Session {
+    let program = testutil::simple_loop();
+    let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+    let env = ExecutorEnv::builder()
+        .segment_limit_po2(14) // 16k cycles
+        .build()
+        .unwrap();
+    ExecutorImpl::new(env, image).unwrap().run().unwrap()
+}
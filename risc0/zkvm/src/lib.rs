@@ -100,14 +100,23 @@ pub use {
         recursion::RECURSION_PO2,
         server::{
             exec::executor::ExecutorImpl,
-            prove::{get_prover_server, HalPair, ProverServer},
+            prove::{
+                get_prover_server, CompressState, HalPair, ProveError, ProverServer,
+                RemoteClient, RemoteStatus, ReproBundle, UploadingProver,
+            },
             session::{
-                FileSegmentRef, NullSegmentRef, Segment, SegmentRef, Session, SessionEvents,
-                SimpleSegmentRef,
+                FileSegmentRef, NullSegmentRef, RecursionPlan, Segment, SegmentRef, Session,
+                SessionEvents, SessionManifest, SimpleSegmentRef,
             },
         },
     },
-    risc0_circuit_rv32im::prove::engine::loader::Loader,
+    risc0_circuit_rv32im::prove::{
+        emu::{
+            exec::{usable_cycles_per_segment, ExecutorError},
+            rv32im::InsnKind,
+        },
+        engine::loader::Loader,
+    },
     risc0_groth16::{
         docker::stark_to_snark, to_json as seal_to_json, ProofJson as Groth16ProofJson,
     },
@@ -122,7 +131,7 @@ pub use {
             env::{ExecutorEnv, ExecutorEnvBuilder},
             prove::{
                 bonsai::BonsaiProver, default_executor, default_prover, external::ExternalProver,
-                Executor, Prover, ProverOpts, ReceiptKind,
+                Executor, LiftCache, Prover, ProverOpts, ReceiptKind,
             },
         },
     },
@@ -131,10 +140,11 @@ pub use {
 #[cfg(not(target_os = "zkvm"))]
 pub use {
     self::host::{
-        prove_info::{ProveInfo, SessionStats},
+        prove_info::{ProveInfo, SegmentProveStats, SessionStats},
         receipt::{
-            Assumption, CompactReceipt, CompositeReceipt, InnerReceipt, Journal, Receipt,
-            SegmentReceipt, SuccinctReceipt, VerifierContext,
+            verify_succinct, Assumption, ClaimTree, CompactReceipt, CompositeReceipt, Endian,
+            InnerReceipt, Journal, Receipt, SegmentReceipt, SuccinctReceipt, VerificationOutcome,
+            VerifierContext,
         },
         recursion::ALLOWED_CONTROL_ROOT,
     },
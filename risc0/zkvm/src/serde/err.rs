@@ -34,6 +34,8 @@ pub enum Error {
     NotSupported,
     /// The serialize buffer is full
     SerializeBufferFull,
+    /// A length does not fit in the u32 length prefix
+    SerializeLengthOverflow,
 }
 
 /// A Result type for `risc0_zkvm::serde` operations that can fail
@@ -50,6 +52,7 @@ impl Display for Error {
             Self::DeserializeUnexpectedEnd => "Unexpected end during deserialization",
             Self::NotSupported => "Not supported",
             Self::SerializeBufferFull => "The serialize buffer is full",
+            Self::SerializeLengthOverflow => "A length does not fit in the u32 length prefix",
         })
     }
 }
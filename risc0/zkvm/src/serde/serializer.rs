@@ -105,6 +105,11 @@ impl<W: WordWrite> Serializer<W> {
     }
 }
 
+// Convert a length to its u32 prefix, rather than silently truncating it.
+fn length_prefix(len: usize) -> Result<u32> {
+    len.try_into().map_err(|_| Error::SerializeLengthOverflow)
+}
+
 impl<'a, W: WordWrite> serde::ser::Serializer for &'a mut Serializer<W> {
     type Ok = ();
     type Error = Error;
@@ -186,7 +191,7 @@ impl<'a, W: WordWrite> serde::ser::Serializer for &'a mut Serializer<W> {
 
     fn serialize_str(self, v: &str) -> Result<()> {
         let bytes = v.as_bytes();
-        self.serialize_u32(bytes.len() as u32)?;
+        self.serialize_u32(length_prefix(bytes.len())?)?;
         self.stream.write_padded_bytes(bytes)
     }
 
@@ -200,7 +205,7 @@ impl<'a, W: WordWrite> serde::ser::Serializer for &'a mut Serializer<W> {
     // B) Use the experimental Rust specialization
     //    features.
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
-        self.serialize_u32(v.len() as u32)?;
+        self.serialize_u32(length_prefix(v.len())?)?;
         self.stream.write_padded_bytes(v)
     }
 
@@ -257,7 +262,7 @@ impl<'a, W: WordWrite> serde::ser::Serializer for &'a mut Serializer<W> {
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
         match len {
             Some(val) => {
-                self.serialize_u32(val.try_into().unwrap())?;
+                self.serialize_u32(length_prefix(val)?)?;
                 Ok(self)
             }
             None => Err(Error::NotSupported),
@@ -290,7 +295,7 @@ impl<'a, W: WordWrite> serde::ser::Serializer for &'a mut Serializer<W> {
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
         match len {
             Some(val) => {
-                self.serialize_u32(val.try_into().unwrap())?;
+                self.serialize_u32(length_prefix(val)?)?;
                 Ok(self)
             }
             None => Err(Error::NotSupported),
@@ -505,4 +510,23 @@ mod tests {
         };
         assert_eq!(expected, to_vec(&input).unwrap().as_slice());
     }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_length_overflow() {
+        // Claims more elements than a u32 length prefix can hold, without allocating them.
+        struct Huge;
+
+        impl Serialize for Huge {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_seq(Some(u32::MAX as usize + 1))?;
+                unreachable!()
+            }
+        }
+
+        assert_eq!(to_vec(&Huge), Err(Error::SerializeLengthOverflow));
+    }
 }
@@ -116,3 +116,76 @@ pub mod rust_crypto {
     /// implementation for usage in the zkVM guest and on the host.
     pub type Sha256 = rust_crypto::Sha256<super::Impl>;
 }
+
+/// Hash `data` with the hash function of the named hash suite.
+///
+/// `suite_name` is one of the hash functions supported by the prover: `sha-256`, `poseidon2` or
+/// `blake2b`. With `sha-256` this is the plain SHA-256 digest of `data`, as used for the journal
+/// digest in a [ReceiptClaim](crate::ReceiptClaim).
+///
+/// The field-based `poseidon2` and `blake2b` suites hash field elements rather than bytes, so
+/// `data` is first packed into little-endian words, zero-padding the last word, and prefixed
+/// with its length in bytes. Each word is then split into 16-bit half words, low half first, as
+/// the recursion prover does for SHA-256 digests, so that every element is below the field
+/// modulus. Unknown suite names return an error.
+#[cfg(not(target_os = "zkvm"))]
+pub fn hash_bytes(suite_name: &str, data: &[u8]) -> anyhow::Result<Digest> {
+    use alloc::vec::Vec;
+
+    use risc0_core::field::baby_bear::BabyBearElem;
+    use risc0_zkp::core::hash::{blake2b::Blake2bCpuHashSuite, poseidon2::Poseidon2HashSuite};
+
+    let suite = match suite_name {
+        "sha-256" => return Ok(*Impl::hash_bytes(data)),
+        "poseidon2" => Poseidon2HashSuite::new_suite(),
+        "blake2b" => Blake2bCpuHashSuite::new_suite(),
+        _ => anyhow::bail!("unknown hash suite: {suite_name}"),
+    };
+    let len = u32::try_from(data.len())?;
+    let words = data.chunks(WORD_SIZE).map(|chunk| {
+        let mut word = [0u8; WORD_SIZE];
+        word[..chunk.len()].copy_from_slice(chunk);
+        u32::from_le_bytes(word)
+    });
+    let elems: Vec<_> = core::iter::once(len)
+        .chain(words)
+        .flat_map(|x| [x & 0xffff, x >> 16])
+        .map(BabyBearElem::new)
+        .collect();
+    Ok(*suite.hashfn.hash_elem_slice(&elems))
+}
+
+#[cfg(test)]
+mod tests {
+    use risc0_core::field::baby_bear::BabyBearElem;
+    use risc0_zkp::core::hash::poseidon2::Poseidon2HashSuite;
+
+    use super::{hash_bytes, Impl, Sha256};
+
+    #[test]
+    fn hash_bytes_by_suite() {
+        let data = b"journal bytes";
+        assert_eq!(hash_bytes("sha-256", data).unwrap(), *Impl::hash_bytes(data));
+
+        let poseidon2 = hash_bytes("poseidon2", data).unwrap();
+        let blake2b = hash_bytes("blake2b", data).unwrap();
+        assert_eq!(poseidon2, hash_bytes("poseidon2", data).unwrap());
+        assert_ne!(poseidon2, blake2b);
+        assert_ne!(poseidon2, hash_bytes("poseidon2", b"other bytes").unwrap());
+
+        // Bytes are packed into words and split into 16-bit half words after a length prefix.
+        let elems = [5, 0, 0x0201, 0x0403, 0x05, 0].map(BabyBearElem::new);
+        let expected = *Poseidon2HashSuite::new_suite().hashfn.hash_elem_slice(&elems);
+        assert_eq!(hash_bytes("poseidon2", &[1, 2, 3, 4, 5]).unwrap(), expected);
+
+        // Zero padding of the last word does not collide with trailing zero bytes.
+        for suite in ["poseidon2", "blake2b"] {
+            let padded = hash_bytes(suite, b"a\0").unwrap();
+            assert_ne!(hash_bytes(suite, b"a").unwrap(), padded);
+            assert_ne!(hash_bytes(suite, b"").unwrap(), hash_bytes(suite, b"\0").unwrap());
+        }
+
+        let err = hash_bytes("md5", data).unwrap_err();
+        assert!(err.to_string().contains("unknown hash suite"), "{err}");
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// These tests enable dev mode through the environment, so they live in their own test binary to
+// avoid racing with the unit tests that expect it to be disabled.
+
+#![cfg(not(feature = "disable-dev-mode"))]
+
+use risc0_binfmt::MemoryImage;
+use risc0_circuit_rv32im::prove::emu::testutil;
+use risc0_zkvm::{
+    get_prover_server, sha::Digest, CompositeReceipt, ExecutorEnv, ExecutorImpl, ExitCode,
+    InnerReceipt, MaybePruned, ProverOpts, Receipt, ReceiptClaim, VerificationOutcome,
+    VerifierContext, PAGE_SIZE,
+};
+
+fn with_dev_mode<R>(f: impl FnOnce() -> R) -> R {
+    temp_env::with_var("RISC0_DEV_MODE", Some("1"), f)
+}
+
+#[test]
+fn fake_receipt() {
+    let claim = ReceiptClaim {
+        pre: MaybePruned::Pruned(Digest::ZERO),
+        post: MaybePruned::Pruned(Digest::ZERO),
+        exit_code: ExitCode::Halted(0),
+        input: Digest::ZERO,
+        output: MaybePruned::Value(None),
+    };
+    let receipt = Receipt::new(InnerReceipt::Fake { claim }, Vec::new());
+    with_dev_mode(|| {
+        assert_eq!(
+            receipt.verify_or_dev(Digest::ZERO),
+            VerificationOutcome::DevModeAccepted
+        );
+    });
+}
+
+#[test]
+fn fake_segment_receipts() {
+    let program = testutil::simple_loop();
+    let image = MemoryImage::new(&program, PAGE_SIZE as u32).unwrap();
+    let image_id = image.compute_id();
+    let env = ExecutorEnv::builder()
+        .segment_limit_po2(14) // 16k cycles
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::new(env, image).unwrap().run().unwrap();
+    assert!(session.segments.len() > 1);
+
+    with_dev_mode(|| {
+        let ctx = VerifierContext::default();
+        let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+        let segments = session
+            .segments
+            .iter()
+            .map(|segment_ref| {
+                let segment = segment_ref.resolve().unwrap();
+                prover.prove_segment(&ctx, &segment).unwrap()
+            })
+            .collect();
+        let composite = CompositeReceipt {
+            segments,
+            assumptions: vec![],
+            journal_digest: None,
+        };
+        let receipt = Receipt::new(InnerReceipt::Composite(composite), Vec::new());
+        assert_eq!(
+            receipt.verify_or_dev(image_id),
+            VerificationOutcome::DevModeAccepted
+        );
+    });
+}
@@ -212,7 +212,7 @@ fn generate_receipt() -> Receipt {
     let session = exec.run().unwrap();
 
     tracing::info!("prove");
-    let prover = get_prover_server(&ProverOpts::compact()).unwrap();
+    let prover = get_prover_server(&ProverOpts::groth16()).unwrap();
 
     prover
         .prove_session(&VerifierContext::default(), &session)